@@ -1,46 +1,113 @@
 use crate::{
     error::ContractError,
     msg::{
-        CollectionExecuteMsg, CollectionQueryMsg, ExecuteMsg, GetPaymentParamsResponse,
-        InstantiateMsg, MigrateMsg, NameServiceExecuteMsgResponse, PaymentParams, QueryMsg,
-        SudoMsg,
+        mint_commitment, validate_mint_phases, ActionAccess, ActionRule, ActiveAuctionsResponse,
+        ActiveMintPhaseResponse, Auction, BatchMode, Bid, BidHistoryResponse, ClaimableResponse,
+        CollectionAction, CollectionExecuteMsg, CollectionInfo, CollectionInstantiateMsg,
+        CollectionPaymentParams, CollectionQueryMsg, CollectionRevenueResponse,
+        CommitDepositRefund, CommitRevealConfig, Config, CurrentMintPriceResponse, CurveShape,
+        ExecuteMsg, ExpiringTokensResponse, ExpiryResponse, GetConfigResponse,
+        GetOwnershipResponse, GetPaymentParamsResponse, InstantiateMsg, ListAcceptedCw20Response,
+        ListCollectionPaymentParamsResponse, ListCollectionsResponse, ManagedCollection,
+        MigrateCollection, MigrateMsg, MintAllowanceResponse, MintCommitment,
+        MintCommitmentResponse, MintFailurePolicy, MintLimits, MintOptions, MintPayout, MintPhase,
+        MintQuotaResponse, NameServiceExecuteMsgResponse, NextMintPricesResponse, PassThroughItem,
+        PassThroughPolicy, PassThroughPolicyResponse, PauseState, PauseStatusResponse,
+        PaymentParams, PriceDecay, QueryMsg, Reassignment, RebatePoolResponse, ReceiveMsg,
+        Registration, SimulateMintResponse, SudoMsg, TokenIdCharset, TokenIdPolicy,
+        ValidateTokenIdResponse, MAX_FEE_BPS, MAX_REGISTRATION_YEARS, MINT_COMMITMENT_LEN,
+        SECONDS_PER_YEAR,
+    },
+    state::{
+        CountedMint, DutchEscrow, PassThroughContext, ReplyContext, ACCEPTED_CW20,
+        ADDRESS_MINT_COUNTS, AUCTIONED_TOKENS, AUCTIONS, AUCTION_BIDS, AUCTION_SEQUENCE,
+        CLAIMABLE_BALANCES, COLLECTIONS, COLLECTION_MINT_COUNTS, COLLECTION_PAUSES,
+        COLLECTION_PAYMENT_PARAMS, COLLECTION_REVENUE, COMMITMENTS_BY_HEIGHT, CONFIG,
+        CONTRACT_NAME, CONTRACT_VERSION, DUTCH_CLEARING_PRICES, DUTCH_ESCROWS, GLOBAL_PAUSE,
+        GUARDIAN, MINT_COMMITMENTS, MINT_LIMITS, MINT_PHASES, OWNER, PASS_THROUGH_POLICIES,
+        PAYMENT_PARAMS, PENDING_OWNER, PHASE_MINT_COUNTS, REBATE_POOLS, REGISTRATIONS,
+        REGISTRATION_EXPIRIES, REPLY_CONTEXTS, REPLY_SEQUENCE, TOKEN_ID_POLICIES,
     },
-    state::{CONTRACT_NAME, CONTRACT_VERSION, PAYMENT_PARAMS},
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, Event,
-    MessageInfo, QueryRequest, QueryResponse, Reply, ReplyOn, Response, StdError, SubMsg, Uint128,
-    WasmMsg, WasmQuery,
+    from_json, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, Event,
+    HexBinary, MessageInfo, Order, QueryRequest, QueryResponse, Reply, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, SubMsgResult, Timestamp, Uint128, WasmMsg, WasmQuery,
 };
 use cw2::{get_contract_version, set_contract_version, ContractVersion, VersionError};
-use cw721::msg::NumTokensResponse;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::msg::{NumTokensResponse, OwnerOfResponse};
+use cw_storage_plus::{Bound, Map};
+use cw_utils::{parse_execute_response_data, parse_reply_instantiate_data};
+use sha2::{Digest, Sha256};
 
 type ContractResult = Result<Response, ContractError>;
 
+const DEFAULT_PAGE_LIMIT: u32 = 10;
+const MAX_PAGE_LIMIT: u32 = 30;
+const DEFAULT_MAX_BATCH_SIZE: u32 = 20;
+const REPLY_CODE_BITS: u32 = 8;
+
+/// Kept in the low bits of reply ids, above which sits a sequence that makes each id unique.
 enum ReplyCode {
     PassThrough = 1,
+    CreateCollection = 2,
+    BatchItem = 3,
+    Reclaim = 4,
 }
 
 impl TryFrom<u64> for ReplyCode {
     type Error = ContractError;
 
     fn try_from(item: u64) -> Result<Self, Self::Error> {
-        match item {
+        match item & ((1 << REPLY_CODE_BITS) - 1) {
             1 => Ok(ReplyCode::PassThrough),
-            _ => panic!("invalid ReplyCode({})", item),
+            2 => Ok(ReplyCode::CreateCollection),
+            3 => Ok(ReplyCode::BatchItem),
+            4 => Ok(ReplyCode::Reclaim),
+            _ => Err(ContractError::UnknownReplyId { id: item }),
         }
     }
 }
 
+fn reply_id(sequence: u64, code: ReplyCode) -> u64 {
+    (sequence << REPLY_CODE_BITS) | code as u64
+}
+
+fn save_reply_context(
+    storage: &mut dyn Storage,
+    code: ReplyCode,
+    context: &ReplyContext,
+) -> StdResult<u64> {
+    let sequence = REPLY_SEQUENCE.may_load(storage)?.unwrap_or_default() + 1;
+    REPLY_SEQUENCE.save(storage, &sequence)?;
+    let id = reply_id(sequence, code);
+    REPLY_CONTEXTS.save(storage, id, context)?;
+    Ok(id)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(deps: DepsMut, _: Env, _: MessageInfo, msg: InstantiateMsg) -> ContractResult {
+pub fn instantiate(
+    deps: DepsMut,
+    _: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> ContractResult {
+    let owner = match msg.owner {
+        None => info.sender,
+        Some(owner) => deps.api.addr_validate(&owner)?,
+    };
+    OWNER.save(deps.storage, &owner)?;
     msg.payment_params.validate()?;
     PAYMENT_PARAMS.save(deps.storage, &msg.payment_params)?;
+    msg.config.validate()?;
+    CONFIG.save(deps.storage, &msg.config)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     let instantiate_event = Event::new("my-collection-manager")
-        .add_attribute("update-contract-version", CONTRACT_VERSION);
+        .add_attribute("update-contract-version", CONTRACT_VERSION)
+        .add_attribute("update-owner", owner);
     let instantiate_event = append_payment_params_attributes(instantiate_event, msg.payment_params);
     Ok(Response::default().add_event(instantiate_event))
 }
@@ -51,242 +118,3904 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
         ExecuteMsg::PassThrough {
             collection,
             message,
-        } => execute_pass_through(deps, env, info, collection, message),
+            mint_options,
+        } => {
+            if matches!(message, CollectionExecuteMsg::Mint { .. }) {
+                assert_direct_mint_allowed(deps.storage)?;
+            }
+            execute_pass_through(deps, env, info, collection, message, mint_options)
+        }
+        ExecuteMsg::BatchPassThrough { items, mode } => {
+            execute_batch_pass_through(deps, env, info, items, mode)
+        }
+        ExecuteMsg::SetCollectionPaymentParams {
+            collection,
+            payment_params,
+        } => {
+            assert_owner(deps.storage, &info.sender)?;
+            set_collection_payment_params(deps, collection, payment_params)
+        }
+        ExecuteMsg::RemoveCollectionPaymentParams { collection } => {
+            assert_owner(deps.storage, &info.sender)?;
+            remove_collection_payment_params(deps, collection)
+        }
+        ExecuteMsg::AddCollection { collection, label } => {
+            assert_owner(deps.storage, &info.sender)?;
+            add_collection(deps, env, collection, label)
+        }
+        ExecuteMsg::RemoveCollection { collection } => {
+            assert_owner(deps.storage, &info.sender)?;
+            remove_collection(deps, collection)
+        }
+        ExecuteMsg::UpdateConfig(config) => {
+            assert_owner(deps.storage, &info.sender)?;
+            update_config(deps, config)
+        }
+        ExecuteMsg::SetMintPhases { collection, phases } => {
+            assert_owner(deps.storage, &info.sender)?;
+            set_mint_phases(deps, collection, phases)
+        }
+        ExecuteMsg::SetMintLimits { collection, limits } => {
+            assert_owner(deps.storage, &info.sender)?;
+            set_mint_limits(deps, collection, limits)
+        }
+        ExecuteMsg::SetTokenIdPolicy { collection, policy } => {
+            assert_owner(deps.storage, &info.sender)?;
+            set_token_id_policy(deps, collection, policy)
+        }
+        ExecuteMsg::SetPassThroughPolicy { collection, policy } => {
+            assert_owner(deps.storage, &info.sender)?;
+            set_pass_through_policy(deps, collection, policy)
+        }
+        ExecuteMsg::CommitMint { commitment } => execute_commit_mint(deps, env, info, commitment),
+        ExecuteMsg::RevealMint {
+            collection,
+            message,
+            salt,
+            mint_options,
+        } => execute_reveal_mint(deps, env, info, collection, message, salt, mint_options),
+        ExecuteMsg::ClearExpiredCommitments { limit } => {
+            execute_clear_expired_commitments(deps, env, limit)
+        }
+        ExecuteMsg::Renew {
+            collection,
+            token_id,
+            years,
+        } => execute_renew(deps, env, info, collection, token_id, years),
+        ExecuteMsg::Reclaim {
+            collection,
+            token_id,
+            reassign,
+        } => {
+            assert_owner(deps.storage, &info.sender)?;
+            execute_reclaim(deps, env, collection, token_id, reassign)
+        }
+        ExecuteMsg::OpenAuction {
+            collection,
+            token_id,
+            reserve_price,
+            end,
+            min_increment,
+        } => {
+            assert_owner(deps.storage, &info.sender)?;
+            execute_open_auction(
+                deps,
+                env,
+                collection,
+                token_id,
+                reserve_price,
+                end,
+                min_increment,
+            )
+        }
+        ExecuteMsg::Bid { auction_id } => execute_bid(deps, env, info, auction_id),
+        ExecuteMsg::SettleAuction { auction_id } => execute_settle_auction(deps, env, auction_id),
+        ExecuteMsg::SettleDutchEscrow { collection, minter } => {
+            execute_settle_dutch_escrow(deps, env, collection, minter)
+        }
+        ExecuteMsg::Withdraw { denoms, amount } => execute_withdraw(deps, info, denoms, amount),
+        ExecuteMsg::FundRebatePool { collection } => {
+            assert_owner(deps.storage, &info.sender)?;
+            fund_rebate_pool(deps, info, collection)
+        }
+        ExecuteMsg::WithdrawRebatePool { collection, amount } => {
+            assert_owner(deps.storage, &info.sender)?;
+            withdraw_rebate_pool(deps, info, collection, amount)
+        }
+        ExecuteMsg::Pause {
+            collection,
+            allowed_actions,
+        } => {
+            assert_owner_or_guardian(deps.storage, &info.sender)?;
+            pause(deps, collection, allowed_actions)
+        }
+        ExecuteMsg::Unpause { collection } => {
+            assert_owner_or_guardian(deps.storage, &info.sender)?;
+            unpause(deps, collection)
+        }
+        ExecuteMsg::SetGuardian { guardian } => {
+            assert_owner(deps.storage, &info.sender)?;
+            set_guardian(deps, guardian)
+        }
+        ExecuteMsg::CreateCollection {
+            name,
+            symbol,
+            label,
+        } => {
+            assert_owner(deps.storage, &info.sender)?;
+            execute_create_collection(deps, env, name, symbol, label)
+        }
+        ExecuteMsg::UpdatePaymentParams(payment_params) => {
+            execute_update_payment_params(deps, info, payment_params)
+        }
+        ExecuteMsg::Receive(receive_msg) => execute_receive(deps, env, info, receive_msg),
+        ExecuteMsg::AddAcceptedCw20 { token } => {
+            assert_owner(deps.storage, &info.sender)?;
+            add_accepted_cw20(deps, token)
+        }
+        ExecuteMsg::RemoveAcceptedCw20 { token } => {
+            assert_owner(deps.storage, &info.sender)?;
+            remove_accepted_cw20(deps, token)
+        }
+        ExecuteMsg::ProposeOwner { new_owner } => execute_propose_owner(deps, info, new_owner),
+        ExecuteMsg::AcceptOwnership => execute_accept_ownership(deps, info),
+        ExecuteMsg::RenounceOwnership => execute_renounce_ownership(deps, info),
     }
 }
 
-fn execute_pass_through(
+fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    match OWNER.may_load(storage)? {
+        Some(owner) if &owner == sender => Ok(()),
+        _ => Err(ContractError::Unauthorized),
+    }
+}
+
+fn assert_owner_or_guardian(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    match GUARDIAN.may_load(storage)? {
+        Some(guardian) if &guardian == sender => Ok(()),
+        _ => assert_owner(storage, sender),
+    }
+}
+
+/// Rejects collection messages that the global pause, or the pause of the collection, stops.
+fn assert_not_paused(
+    storage: &dyn Storage,
+    collection: &Addr,
+    message: &CollectionExecuteMsg,
+) -> Result<(), ContractError> {
+    let action = CollectionAction::of(message);
+    let pauses = [
+        GLOBAL_PAUSE.may_load(storage)?,
+        COLLECTION_PAUSES.may_load(storage, collection)?,
+    ];
+    for pause in pauses.iter().flatten() {
+        if !pause.allowed_actions.contains(&action) {
+            return Err(ContractError::Paused {
+                collection: collection.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks the sender against the access the policy of the collection gives to the action of the
+/// message.
+fn assert_pass_through_allowed(
+    storage: &dyn Storage,
+    collection: &Addr,
+    sender: &Addr,
+    message: &CollectionExecuteMsg,
+) -> Result<(), ContractError> {
+    let action = CollectionAction::of(message);
+    let access = match PASS_THROUGH_POLICIES.may_load(storage, collection)? {
+        None => action.default_access(),
+        Some(policy) => policy.access(&action),
+    };
+    match access {
+        ActionAccess::Public => Ok(()),
+        ActionAccess::OwnerOnly => match OWNER.may_load(storage)? {
+            Some(owner) if &owner == sender => Ok(()),
+            _ => Err(ContractError::OwnerOnlyAction {
+                action: action.as_str().to_owned(),
+            }),
+        },
+        ActionAccess::Forbidden => Err(ContractError::ForbiddenAction {
+            action: action.as_str().to_owned(),
+            collection: collection.to_string(),
+        }),
+    }
+}
+
+/// The manager can update the info of every token as creator of the collection, and operates the
+/// tokens of owners who approved it, so actions on a token are only passed through for its owner.
+fn assert_token_owner(
+    deps: Deps,
+    collection: &Addr,
+    sender: &Addr,
+    message: &CollectionExecuteMsg,
+) -> Result<(), ContractError> {
+    let (CollectionExecuteMsg::Burn { token_id }
+    | CollectionExecuteMsg::TransferNft { token_id, .. }
+    | CollectionExecuteMsg::SendNft { token_id, .. }
+    | CollectionExecuteMsg::Approve { token_id, .. }
+    | CollectionExecuteMsg::Revoke { token_id, .. }
+    | CollectionExecuteMsg::UpdateNftInfo { token_id, .. }) = message
+    else {
+        return Ok(());
+    };
+    let owner = deps
+        .querier
+        .query_wasm_smart::<OwnerOfResponse>(
+            collection,
+            &CollectionQueryMsg::OwnerOf {
+                token_id: token_id.to_owned(),
+                include_expired: None,
+            },
+        )?
+        .owner;
+    if owner != sender.as_str() {
+        return Err(ContractError::NotTokenOwner {
+            sender: sender.to_string(),
+            token_id: token_id.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+fn execute_update_payment_params(
     deps: DepsMut,
-    _: Env,
     info: MessageInfo,
-    collection: String,
-    message: CollectionExecuteMsg,
+    payment_params: PaymentParams,
 ) -> ContractResult {
-    let response = Response::default();
-    let response = match message {
-        CollectionExecuteMsg::Mint { .. } => match handle_pre_mint_funds(&deps, &info) {
-            Err(err) => Err(err)?,
-            Ok(bank_msgs) => response.add_messages(bank_msgs),
-        },
-        _ => {
-            if !info.funds.is_empty() {
-                let refund_msg = BankMsg::Send {
-                    to_address: info.sender.to_string(),
-                    amount: info.funds,
-                };
-                response.add_message(refund_msg)
-            } else {
-                response
-            }
+    assert_owner(deps.storage, &info.sender)?;
+    payment_params.validate()?;
+    PAYMENT_PARAMS.save(deps.storage, &payment_params)?;
+    let update_event = Event::new("my-collection-manager");
+    let update_event = append_payment_params_attributes(update_event, payment_params);
+    Ok(Response::default().add_event(update_event))
+}
+
+fn execute_propose_owner(deps: DepsMut, info: MessageInfo, new_owner: String) -> ContractResult {
+    assert_owner(deps.storage, &info.sender)?;
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    PENDING_OWNER.save(deps.storage, &new_owner)?;
+    let propose_event =
+        Event::new("my-collection-manager").add_attribute("propose-owner", new_owner);
+    Ok(Response::default().add_event(propose_event))
+}
+
+fn execute_accept_ownership(deps: DepsMut, info: MessageInfo) -> ContractResult {
+    match PENDING_OWNER.may_load(deps.storage)? {
+        None => Err(ContractError::NoPendingOwner),
+        Some(pending_owner) if pending_owner != info.sender => Err(ContractError::Unauthorized),
+        Some(pending_owner) => {
+            OWNER.save(deps.storage, &pending_owner)?;
+            PENDING_OWNER.remove(deps.storage);
+            let accept_event =
+                Event::new("my-collection-manager").add_attribute("update-owner", pending_owner);
+            Ok(Response::default().add_event(accept_event))
         }
+    }
+}
+
+fn execute_renounce_ownership(deps: DepsMut, info: MessageInfo) -> ContractResult {
+    assert_owner(deps.storage, &info.sender)?;
+    OWNER.remove(deps.storage);
+    PENDING_OWNER.remove(deps.storage);
+    let renounce_event = Event::new("my-collection-manager").add_attribute("update-owner", "none");
+    Ok(Response::default().add_event(renounce_event))
+}
+
+fn execute_create_collection(
+    deps: DepsMut,
+    env: Env,
+    name: String,
+    symbol: String,
+    label: String,
+) -> ContractResult {
+    let code_id = CONFIG
+        .load(deps.storage)?
+        .collection_code_id
+        .ok_or(ContractError::MissingCollectionCodeId)?;
+    let instantiate_msg = CollectionInstantiateMsg {
+        name,
+        symbol,
+        collection_info_extension: None,
+        minter: Some(env.contract.address.to_string()),
+        creator: None,
+        withdraw_address: None,
     };
-    let onward_exec_msg = WasmMsg::Execute {
-        contract_addr: collection.to_owned(),
-        msg: to_json_binary(&message)?,
+    let onward_instantiate_msg = WasmMsg::Instantiate {
+        admin: Some(env.contract.address.to_string()),
+        code_id,
+        msg: to_json_binary(&instantiate_msg)?,
         funds: vec![],
+        label: label.to_owned(),
     };
+    let reply_id = save_reply_context(
+        deps.storage,
+        ReplyCode::CreateCollection,
+        &ReplyContext::CreateCollection(CollectionInfo {
+            label,
+            code_id,
+            created_at: env.block.time,
+        }),
+    )?;
     let onward_sub_msg = SubMsg {
-        id: ReplyCode::PassThrough as u64,
-        msg: CosmosMsg::<Empty>::Wasm(onward_exec_msg),
+        id: reply_id,
+        msg: CosmosMsg::<Empty>::Wasm(onward_instantiate_msg),
         reply_on: ReplyOn::Success,
         gas_limit: None,
     };
-    let token_count_result =
-        deps.querier
-            .query::<NumTokensResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr: collection,
-                msg: to_json_binary(&CollectionQueryMsg::NumTokens {})?,
-            }));
-    let token_count_event = Event::new("my-collection-manager")
-        .add_attribute("token-count-before", token_count_result?.count.to_string());
-    Ok(response
-        .add_submessage(onward_sub_msg)
-        .add_event(token_count_event))
+    Ok(Response::default().add_submessage(onward_sub_msg))
 }
 
-fn handle_pre_mint_funds(
-    deps: &DepsMut,
-    info: &MessageInfo,
-) -> Result<Vec<BankMsg>, ContractError> {
-    let payment_params = PAYMENT_PARAMS.load(deps.storage)?;
-    let (payment, change) = match payment_params.mint_price {
-        None => (None, info.funds.to_owned()),
-        Some(minting_price) if minting_price.amount.le(&Uint128::zero()) => {
-            Err(ContractError::ZeroPrice)?
-        }
-        Some(minting_price) => {
-            let (aggregated, mut others) = split_fund_denom(&minting_price.denom, &info.funds);
-            match aggregated.checked_sub(minting_price.amount) {
-                Err(_) => Err(ContractError::MissingPayment {
-                    missing_payment: minting_price.to_owned(),
-                })?,
-                Ok(change_in_denom) if change_in_denom.le(&Uint128::zero()) => {}
-                Ok(change_in_denom) => others.push(Coin {
-                    denom: minting_price.denom.clone(),
-                    amount: change_in_denom,
-                }),
-            };
-            (Some(minting_price), others)
-        }
-    };
-    let mut bank_msgs = Vec::<BankMsg>::new();
-    if let Some(paid) = payment {
-        bank_msgs.push(BankMsg::Send {
-            to_address: payment_params.beneficiary.to_string(),
-            amount: vec![paid],
-        });
+/// Mints have to go through `RevealMint` while commit-reveal is required.
+fn assert_direct_mint_allowed(storage: &dyn Storage) -> Result<(), ContractError> {
+    match CONFIG.load(storage)?.commit_reveal {
+        Some(CommitRevealConfig { required: true, .. }) => Err(ContractError::CommitRevealRequired),
+        _ => Ok(()),
     }
-    if !change.is_empty() {
-        bank_msgs.push(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: change,
-        })
-    };
-    Ok(bank_msgs)
 }
 
-fn split_fund_denom(denom: &String, funds: &[Coin]) -> (Uint128, Vec<Coin>) {
-    let (amount, others) = funds.iter().fold(
-        (Uint128::zero(), Vec::with_capacity(funds.len())),
-        |(aggregated, mut others), fund| {
-            if &fund.denom == denom {
-                (aggregated.strict_add(fund.amount), others)
-            } else {
-                others.push(fund.clone());
-                (aggregated, others)
-            }
+fn execute_commit_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    commitment: HexBinary,
+) -> ContractResult {
+    let commit_reveal = CONFIG
+        .load(deps.storage)?
+        .commit_reveal
+        .ok_or(ContractError::CommitRevealDisabled)?;
+    if commitment.len() != MINT_COMMITMENT_LEN {
+        return Err(ContractError::InvalidCommitmentLength {
+            len: MINT_COMMITMENT_LEN,
+        });
+    }
+    let deposit: Vec<Coin> = commit_reveal.deposit.into_iter().collect();
+    if info.funds != deposit {
+        return Err(ContractError::InvalidCommitDeposit { deposit });
+    }
+    if MINT_COMMITMENTS.has(deps.storage, commitment.as_slice()) {
+        return Err(ContractError::CommitmentExists);
+    }
+    MINT_COMMITMENTS.save(
+        deps.storage,
+        commitment.as_slice(),
+        &MintCommitment {
+            sender: info.sender.to_owned(),
+            height: env.block.height,
+            deposit: deposit.into_iter().next(),
         },
-    );
-    (amount, others)
+    )?;
+    COMMITMENTS_BY_HEIGHT.save(
+        deps.storage,
+        (env.block.height, commitment.as_slice()),
+        &Empty {},
+    )?;
+    let commit_event = Event::new("my-collection-manager")
+        .add_attribute("commit-mint", commitment.to_hex())
+        .add_attribute("commit-mint-sender", info.sender);
+    Ok(Response::default().add_event(commit_event))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> ContractResult {
-    match ReplyCode::try_from(msg.id)? {
-        ReplyCode::PassThrough => reply_pass_through(deps, env, msg),
+fn execute_reveal_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    message: CollectionExecuteMsg,
+    salt: HexBinary,
+    mint_options: Option<MintOptions>,
+) -> ContractResult {
+    let commit_reveal = CONFIG
+        .load(deps.storage)?
+        .commit_reveal
+        .ok_or(ContractError::CommitRevealDisabled)?;
+    let CollectionExecuteMsg::Mint {
+        ref token_id,
+        ref owner,
+        ..
+    } = message
+    else {
+        return Err(ContractError::RevealNotMint);
+    };
+    let commitment = mint_commitment(token_id, owner, &salt, info.sender.as_str());
+    let committed = MINT_COMMITMENTS
+        .may_load(deps.storage, commitment.as_slice())?
+        .ok_or(ContractError::UnknownCommitment)?;
+    let reveal_from = committed.height + commit_reveal.min_blocks;
+    if env.block.height < reveal_from {
+        return Err(ContractError::RevealTooEarly { reveal_from });
     }
+    let expired_at = committed.height + commit_reveal.max_blocks;
+    if expired_at <= env.block.height {
+        return Err(ContractError::CommitmentExpired { expired_at });
+    }
+    remove_commitment(deps.storage, &commitment, committed.height);
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    let deposit_msgs = settle_commit_deposit(
+        deps.storage,
+        &committed,
+        commit_reveal.deposit_refund != CommitDepositRefund::Never,
+        Some(&collection_addr),
+    )?;
+    let response = execute_pass_through(deps, env, info, collection, message, mint_options)?;
+    let reveal_event =
+        Event::new("my-collection-manager").add_attribute("reveal-mint", commitment.to_hex());
+    Ok(response.add_messages(deposit_msgs).add_event(reveal_event))
 }
 
-fn reply_pass_through(_deps: DepsMut, _env: Env, msg: Reply) -> ContractResult {
-    let resp = msg.result.into_result().map_err(StdError::generic_err)?;
-    let data = if let Some(data) = resp.data {
-        data.0[2..].to_vec()
-    } else {
-        return Ok(Response::default());
-    };
-    let value = if let Ok(value) = from_json::<NameServiceExecuteMsgResponse>(data) {
-        value
-    } else {
-        return Ok(Response::default());
+fn execute_clear_expired_commitments(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> ContractResult {
+    let commit_reveal = CONFIG.load(deps.storage)?.commit_reveal;
+    // Without commit-reveal no commitment can be revealed anymore, so they all count as expired
+    // and their deposits go back.
+    let (max, refund) = match commit_reveal {
+        None => (None, true),
+        Some(commit_reveal) => match env.block.height.checked_sub(commit_reveal.max_blocks) {
+            None => (Some(Bound::exclusive((0, &[] as &[u8]))), false),
+            Some(last_expired) => (
+                Some(Bound::exclusive((last_expired + 1, &[] as &[u8]))),
+                commit_reveal.deposit_refund == CommitDepositRefund::Always,
+            ),
+        },
     };
-    let event = Event::new("my-collection-manager")
-        .add_attribute("token-count-after", value.num_tokens.to_string());
-    Ok(Response::default().add_event(event))
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let expired = COMMITMENTS_BY_HEIGHT
+        .keys(deps.storage, None, max, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut deposit_msgs = vec![];
+    for (height, commitment) in &expired {
+        let commitment = HexBinary::from(commitment.as_slice());
+        let committed = MINT_COMMITMENTS.load(deps.storage, commitment.as_slice())?;
+        remove_commitment(deps.storage, &commitment, *height);
+        deposit_msgs.extend(settle_commit_deposit(
+            deps.storage,
+            &committed,
+            refund,
+            None,
+        )?);
+    }
+    let clear_event = Event::new("my-collection-manager")
+        .add_attribute("clear-expired-commitments", expired.len().to_string());
+    Ok(Response::default()
+        .add_messages(deposit_msgs)
+        .add_event(clear_event))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
-    match msg {
-        QueryMsg::GetPaymentParams {} => Ok(to_json_binary(&GetPaymentParamsResponse {
-            payment_params: PAYMENT_PARAMS.load(deps.storage)?,
-        })?),
+fn execute_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    mut denoms: Vec<String>,
+    amount: Option<Uint128>,
+) -> ContractResult {
+    let balances = if denoms.is_empty() {
+        CLAIMABLE_BALANCES
+            .prefix(&info.sender)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+    } else {
+        denoms.sort();
+        denoms.dedup();
+        denoms
+            .into_iter()
+            .map(|denom| {
+                let balance = CLAIMABLE_BALANCES
+                    .may_load(deps.storage, (&info.sender, &denom))?
+                    .unwrap_or_default();
+                Ok((denom, balance))
+            })
+            .collect::<StdResult<Vec<_>>>()?
+    };
+    let mut withdrawn = vec![];
+    for (denom, balance) in balances {
+        let withdraw = amount.unwrap_or(balance);
+        if withdraw > balance {
+            return Err(ContractError::InsufficientClaimable {
+                denom,
+                claimable: balance,
+            });
+        }
+        if withdraw == balance {
+            CLAIMABLE_BALANCES.remove(deps.storage, (&info.sender, &denom));
+        } else {
+            CLAIMABLE_BALANCES.save(deps.storage, (&info.sender, &denom), &(balance - withdraw))?;
+        }
+        if !withdraw.is_zero() {
+            withdrawn.push(Coin {
+                denom,
+                amount: withdraw,
+            });
+        }
     }
+    if withdrawn.is_empty() {
+        return Err(ContractError::NothingToWithdraw);
+    }
+    let withdraw_event = Event::new("my-collection-manager")
+        .add_attribute("withdraw", info.sender.to_owned())
+        .add_attribute(
+            "withdraw-amount",
+            withdrawn
+                .iter()
+                .map(Coin::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: withdrawn,
+        })
+        .add_event(withdraw_event))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> ContractResult {
-    match msg {
-        SudoMsg::UpdatePaymentParams(payment_params) => {
-            sudo_update_payment_params(deps, payment_params)
-        }
+fn fund_rebate_pool(deps: DepsMut, info: MessageInfo, collection: String) -> ContractResult {
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    if info.funds.is_empty() {
+        return Err(ContractError::NoRebatePoolFunds);
+    }
+    for coin in &info.funds {
+        REBATE_POOLS.update(
+            deps.storage,
+            (&collection_addr, &coin.denom),
+            |pool| -> StdResult<_> { Ok(pool.unwrap_or_default().strict_add(coin.amount)) },
+        )?;
     }
+    let fund_event = Event::new("my-collection-manager")
+        .add_attribute("fund-rebate-pool", collection_addr)
+        .add_attribute(
+            "fund-rebate-pool-amount",
+            info.funds
+                .iter()
+                .map(Coin::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    Ok(Response::default().add_event(fund_event))
 }
 
-fn sudo_update_payment_params(deps: DepsMut, payment_params: PaymentParams) -> ContractResult {
-    payment_params.validate()?;
-    PAYMENT_PARAMS.save(deps.storage, &payment_params)?;
-    let sudo_event = Event::new("my-collection-manager");
-    let sudo_event = append_payment_params_attributes(sudo_event, payment_params);
-    Ok(Response::default().add_event(sudo_event))
+fn withdraw_rebate_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: String,
+    amount: Coin,
+) -> ContractResult {
+    let collection_addr = deps.api.addr_validate(&collection)?;
+    if amount.amount.is_zero() {
+        return Err(ContractError::NothingToWithdraw);
+    }
+    draw_rebate_pool(deps.storage, &collection_addr, &amount)?;
+    let withdraw_event = Event::new("my-collection-manager")
+        .add_attribute("withdraw-rebate-pool", collection_addr)
+        .add_attribute("withdraw-rebate-pool-amount", amount.to_string());
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![amount],
+        })
+        .add_event(withdraw_event))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> ContractResult {
-    if let Ok(ContractVersion {
-        contract: _,
-        version,
-    }) = get_contract_version(deps.storage)
-    {
-        return Err(ContractError::Version(VersionError::WrongVersion {
-            expected: "0.0.0".to_owned(),
-            found: version,
-        }));
+fn draw_rebate_pool(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    amount: &Coin,
+) -> Result<(), ContractError> {
+    let key = (collection, amount.denom.as_str());
+    let pool = REBATE_POOLS.may_load(storage, key)?.unwrap_or_default();
+    let Ok(rest) = pool.checked_sub(amount.amount) else {
+        return Err(ContractError::RebatePoolTooLow {
+            collection: collection.to_string(),
+            amount: amount.to_owned(),
+        });
+    };
+    if rest.is_zero() {
+        REBATE_POOLS.remove(storage, key);
+        Ok(())
+    } else {
+        Ok(REBATE_POOLS.save(storage, key, &rest)?)
     }
-    msg.payment_params.validate()?;
-    PAYMENT_PARAMS.save(deps.storage, &msg.payment_params)?;
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    let migrate_event = Event::new("my-collection-manager")
-        .add_attribute("update-contract-version", CONTRACT_VERSION);
-    let migrate_event = append_payment_params_attributes(migrate_event, msg.payment_params);
-    Ok(Response::default().add_event(migrate_event))
 }
 
-fn append_payment_params_attributes(my_event: Event, payment_params: PaymentParams) -> Event {
-    let my_event = my_event.add_attribute(
-        "update-payment-params-beneficiary",
-        payment_params.beneficiary,
-    );
-    match payment_params.mint_price {
-        None => my_event.add_attribute("update-payment-params-mint-price", "none"),
-        Some(mint_price) => my_event
-            .add_attribute("update-payment-params-mint-price-denom", mint_price.denom)
-            .add_attribute(
-                "update-payment-params-mint-price-amount",
-                mint_price.amount.to_string(),
-            ),
+/// Draws what the rebate payouts add up to from the rebate pool of the collection.
+fn draw_rebate(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    rebate: &[MintPayout],
+) -> Result<(), ContractError> {
+    match rebate_amount(rebate) {
+        None => Ok(()),
+        Some(amount) => draw_rebate_pool(storage, collection, &amount),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        contract::ReplyCode,
-        msg::{
-            CollectionExecuteMsg, CollectionQueryMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
-            NameServiceExecuteMsgResponse, PaymentParams, SudoMsg,
-        },
-        state::PAYMENT_PARAMS,
-    };
-    use cosmwasm_std::{
-        from_json,
-        testing::{self, MockApi, MockQuerier, MockStorage},
-        to_json_binary, Addr, BankMsg, Binary, Coin, ContractResult, CosmosMsg, Empty, Event,
-        OwnedDeps, Querier, QuerierResult, QueryRequest, Reply, ReplyOn, Response, SubMsg,
-        SubMsgResponse, SubMsgResult, SystemError, SystemResult, Uint128, WasmMsg, WasmQuery,
+/// Puts the rebate of a collection call that failed back in the rebate pool.
+fn refill_rebate_pool(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    rebate: &[MintPayout],
+) -> StdResult<()> {
+    let Some(amount) = rebate_amount(rebate) else {
+        return Ok(());
     };
-    use cw2::{assert_contract_version, ContractVersion};
-    use cw721::msg::NumTokensResponse;
-    use std::marker::PhantomData;
+    REBATE_POOLS.update(
+        storage,
+        (collection, &amount.denom),
+        |pool| -> StdResult<_> { Ok(pool.unwrap_or_default().strict_add(amount.amount)) },
+    )?;
+    Ok(())
+}
 
-    pub fn mock_deps(
-        response: NumTokensResponse,
-    ) -> OwnedDeps<MockStorage, MockApi, NumTokensMockQuerier, Empty> {
-        OwnedDeps {
-            storage: MockStorage::default(),
-            api: MockApi::default(),
-            querier: NumTokensMockQuerier::new(MockQuerier::new(&[]), response),
-            custom_query_type: PhantomData,
+/// Rebates are paid in the denom of their price, which their payouts add up to.
+fn rebate_amount(rebate: &[MintPayout]) -> Option<Coin> {
+    let first = rebate.first()?;
+    Some(Coin {
+        denom: first.amount.denom.to_owned(),
+        amount: rebate.iter().fold(Uint128::zero(), |sum, payout| {
+            sum.strict_add(payout.amount.amount)
+        }),
+    })
+}
+
+/// Settles every escrow of the minter in the dutch auctions of the collection that ended.
+fn execute_settle_dutch_escrow(
+    deps: DepsMut,
+    env: Env,
+    collection: String,
+    minter: String,
+) -> ContractResult {
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    let minter = deps.api.addr_validate(&minter)?;
+    let escrows = DUTCH_ESCROWS
+        .prefix((&collection_addr, &minter))
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let Some((_, first_escrow)) = escrows.first() else {
+        return Err(ContractError::NoDutchEscrow {
+            minter: minter.to_string(),
+        });
+    };
+    let (ended, running): (Vec<_>, Vec<_>) = escrows
+        .iter()
+        .partition(|(_, escrow)| escrow.ends_at <= env.block.time);
+    if ended.is_empty() {
+        return Err(ContractError::DutchAuctionNotOver {
+            ends_at: running
+                .iter()
+                .map(|(_, escrow)| escrow.ends_at)
+                .min()
+                .unwrap_or(first_escrow.ends_at),
+        });
+    }
+    let payment_params = load_payment_params(deps.storage, &collection_addr)?;
+    let mut response = Response::default();
+    for (start, escrow) in ended {
+        DUTCH_ESCROWS.remove(deps.storage, (&collection_addr, &minter, *start));
+        let clearing_price =
+            DUTCH_CLEARING_PRICES.load(deps.storage, (&collection_addr, *start))?;
+        // The payees already got the part of each payment up to the end price.
+        let earned = clearing_price
+            .checked_mul(escrow.minted.into())
+            .map_err(StdError::from)?
+            .saturating_sub(escrow.paid - escrow.held)
+            .min(escrow.held);
+        let refund = escrow.held - earned;
+        let mut bank_msgs = if earned.is_zero() {
+            vec![]
+        } else {
+            let earned = Coin {
+                denom: escrow.denom.to_owned(),
+                amount: earned,
+            };
+            pay_out(
+                deps.storage,
+                &collection_addr,
+                split_payouts(&payment_params, &earned),
+            )?
+        };
+        if !refund.is_zero() {
+            bank_msgs.push(BankMsg::Send {
+                to_address: minter.to_string(),
+                amount: vec![Coin {
+                    denom: escrow.denom.to_owned(),
+                    amount: refund,
+                }],
+            });
         }
+        let settle_event = Event::new("my-collection-manager")
+            .add_attribute("settle-dutch-escrow", minter.to_owned())
+            .add_attribute("settle-dutch-escrow-start", escrow.start.to_string())
+            .add_attribute("settle-dutch-escrow-clearing-price", clearing_price)
+            .add_attribute("settle-dutch-escrow-refund", refund);
+        response = response.add_messages(bank_msgs).add_event(settle_event);
     }
+    Ok(response)
+}
 
-    pub struct NumTokensMockQuerier {
-        base: MockQuerier,
-        response: NumTokensResponse,
-    }
+fn remove_commitment(storage: &mut dyn Storage, commitment: &HexBinary, height: u64) {
+    MINT_COMMITMENTS.remove(storage, commitment.as_slice());
+    COMMITMENTS_BY_HEIGHT.remove(storage, (height, commitment.as_slice()));
+}
 
-    impl Querier for NumTokensMockQuerier {
-        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
-            match from_json::<QueryRequest<Empty>>(bin_request) {
+/// Sends the deposit of a commitment back to its sender, or pays it out as revenue when
+/// forfeited. Expired commitments never name their collection, so their deposits are split by
+/// the default payment params and not counted as revenue of any collection.
+fn settle_commit_deposit(
+    storage: &mut dyn Storage,
+    committed: &MintCommitment,
+    refund: bool,
+    collection: Option<&Addr>,
+) -> StdResult<Vec<BankMsg>> {
+    let Some(deposit) = &committed.deposit else {
+        return Ok(vec![]);
+    };
+    if refund {
+        return Ok(vec![BankMsg::Send {
+            to_address: committed.sender.to_string(),
+            amount: vec![deposit.to_owned()],
+        }]);
+    }
+    match collection {
+        Some(collection) => {
+            let payment_params = load_payment_params(storage, collection)?;
+            pay_out(storage, collection, split_payouts(&payment_params, deposit))
+        }
+        None => {
+            let payment_params = PAYMENT_PARAMS.load(storage)?;
+            pay_or_accrue(storage, split_payouts(&payment_params, deposit))
+        }
+    }
+}
+
+fn execute_pass_through(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    mut message: CollectionExecuteMsg,
+    mint_options: Option<MintOptions>,
+) -> ContractResult {
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    assert_not_paused(deps.storage, &collection_addr, &message)?;
+    assert_pass_through_allowed(deps.storage, &collection_addr, &info.sender, &message)?;
+    assert_token_owner(deps.as_ref(), &collection_addr, &info.sender, &message)?;
+    apply_token_id_policy(deps.storage, &collection_addr, &mut message)?;
+    let mut counted_mint = match message {
+        CollectionExecuteMsg::Mint { ref token_id, .. } => Some(record_mint(
+            deps.storage,
+            env.block.time,
+            &collection_addr,
+            &info.sender,
+            token_id,
+            mint_options.as_ref(),
+        )?),
+        _ => None,
+    };
+    let catch_failure = matches!(message, CollectionExecuteMsg::Mint { .. })
+        && matches!(
+            CONFIG.load(deps.storage)?.mint_failure_policy,
+            MintFailurePolicy::Refund { .. }
+        );
+    let response = Response::default();
+    let (response, payment, held_payouts) = match message {
+        CollectionExecuteMsg::Mint { ref token_id, .. } => {
+            let (plan, bank_msgs) = handle_pre_mint_funds(
+                &mut deps,
+                env.block.time,
+                &info,
+                &collection_addr,
+                token_id,
+                mint_options.as_ref(),
+                catch_failure,
+            )?;
+            if let (Some(counted_mint), Some(dutch_escrow)) = (&mut counted_mint, plan.dutch_escrow)
+            {
+                add_dutch_escrow(deps.storage, &collection_addr, &info.sender, &dutch_escrow)?;
+                counted_mint.dutch_escrow = Some(dutch_escrow);
+            }
+            let held_payouts = if catch_failure { plan.payouts } else { vec![] };
+            (response.add_messages(bank_msgs), plan.payment, held_payouts)
+        }
+        _ => {
+            let plan = plan_action_fee(
+                deps.as_ref(),
+                &collection_addr,
+                &info.sender,
+                &CollectionAction::of(&message),
+                &info.funds,
+            )?;
+            draw_rebate(deps.storage, &collection_addr, &plan.rebate)?;
+            let rebate = rebate_amount(&plan.rebate);
+            let mut bank_msgs = pay_out(deps.storage, &collection_addr, plan.payouts)?;
+            bank_msgs.extend(pay_or_accrue(deps.storage, plan.rebate)?);
+            if !plan.refund.is_empty() {
+                bank_msgs.push(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: plan.refund,
+                });
+            }
+            let response = match (plan.payment, rebate) {
+                (Some(fee), _) => response.add_event(
+                    Event::new("my-collection-manager")
+                        .add_attribute("action-fee", fee.to_string()),
+                ),
+                (None, Some(rebate)) => response.add_event(
+                    Event::new("my-collection-manager")
+                        .add_attribute("action-rebate", rebate.to_string()),
+                ),
+                (None, None) => response,
+            };
+            (response.add_messages(bank_msgs), None, vec![])
+        }
+    };
+    let reply_id = save_reply_context(
+        deps.storage,
+        ReplyCode::PassThrough,
+        &ReplyContext::PassThrough(PassThroughContext {
+            sender: info.sender,
+            collection: collection_addr,
+            token_id: message_token_id(&message),
+            payment: payment.to_owned(),
+            counted_mint,
+            held_payouts,
+            held_rebate: vec![],
+        }),
+    )?;
+    forward_to_collection(
+        deps.as_ref(),
+        response,
+        collection,
+        message,
+        payment.map(|payment| payment.to_string()),
+        reply_id,
+        if catch_failure {
+            ReplyOn::Always
+        } else {
+            ReplyOn::Success
+        },
+    )
+}
+
+/// Checks the token id of a mint against the policy of the collection, and rewrites it the way
+/// the policy normalizes it.
+fn apply_token_id_policy(
+    storage: &dyn Storage,
+    collection: &Addr,
+    message: &mut CollectionExecuteMsg,
+) -> Result<(), ContractError> {
+    if let CollectionExecuteMsg::Mint { token_id, .. } = message {
+        *token_id = normalize_token_id(storage, collection, token_id)?;
+    }
+    Ok(())
+}
+
+fn normalize_token_id(
+    storage: &dyn Storage,
+    collection: &Addr,
+    token_id: &str,
+) -> Result<String, ContractError> {
+    match TOKEN_ID_POLICIES.may_load(storage, collection)? {
+        None => Ok(token_id.to_owned()),
+        Some(policy) => policy.normalize(token_id),
+    }
+}
+
+fn message_token_id(message: &CollectionExecuteMsg) -> Option<String> {
+    match message {
+        CollectionExecuteMsg::Mint { token_id, .. }
+        | CollectionExecuteMsg::Burn { token_id }
+        | CollectionExecuteMsg::TransferNft { token_id, .. }
+        | CollectionExecuteMsg::SendNft { token_id, .. }
+        | CollectionExecuteMsg::Approve { token_id, .. }
+        | CollectionExecuteMsg::Revoke { token_id, .. } => Some(token_id.to_owned()),
+        _ => None,
+    }
+}
+
+/// Counts the mint against the mint phases and limits of the collection, failing when they do not
+/// allow it.
+fn record_mint(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    collection: &Addr,
+    minter: &Addr,
+    token_id: &str,
+    mint_options: Option<&MintOptions>,
+) -> Result<CountedMint, ContractError> {
+    if AUCTIONED_TOKENS.has(storage, (collection, token_id)) {
+        return Err(ContractError::TokenIdInAuction {
+            token_id: token_id.to_owned(),
+        });
+    }
+    if let Some(max_supply) = reached_max_supply(storage, collection)? {
+        return Err(ContractError::MaxSupplyReached { max_supply });
+    }
+    let limits = MINT_LIMITS
+        .may_load(storage, collection)?
+        .unwrap_or_default();
+    let total_minted = COLLECTION_MINT_COUNTS
+        .may_load(storage, collection)?
+        .unwrap_or_default();
+    let minted_by_address = ADDRESS_MINT_COUNTS
+        .may_load(storage, (collection, minter))?
+        .unwrap_or_default();
+    if let Some(limit) = limits.per_address {
+        if limit <= minted_by_address {
+            return Err(ContractError::AddressMintLimitReached { limit });
+        }
+    }
+    // Nothing is written until every check passed, so a failed mint leaves no counts behind.
+    let registration = record_registration(storage, now, collection, token_id, mint_options)?;
+    let phase = match record_phase_mint(storage, now, collection, minter, mint_options) {
+        Ok(phase) => phase,
+        Err(err) => {
+            if let Some(registration) = &registration {
+                remove_registration(storage, registration);
+            }
+            return Err(err);
+        }
+    };
+    COLLECTION_MINT_COUNTS.save(storage, collection, &(total_minted + 1))?;
+    ADDRESS_MINT_COUNTS.save(storage, (collection, minter), &(minted_by_address + 1))?;
+    Ok(CountedMint {
+        phase,
+        registration,
+        dutch_escrow: None,
+    })
+}
+
+/// The max supply of the collection, when its mints reached it.
+fn reached_max_supply(storage: &dyn Storage, collection: &Addr) -> StdResult<Option<u64>> {
+    let Some(max_supply) = MINT_LIMITS
+        .may_load(storage, collection)?
+        .and_then(|limits| limits.max_supply)
+    else {
+        return Ok(None);
+    };
+    let total_minted = COLLECTION_MINT_COUNTS
+        .may_load(storage, collection)?
+        .unwrap_or_default();
+    Ok((max_supply <= total_minted).then_some(max_supply))
+}
+
+/// Counts the mint of a settled auction, which the mint phases and per-address limits do not
+/// apply to.
+fn record_auction_mint(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    collection: &Addr,
+    winner: &Addr,
+    token_id: &str,
+    registers: bool,
+) -> Result<CountedMint, ContractError> {
+    let registration = match registers {
+        false => None,
+        true => Some(register_token(
+            storage,
+            now,
+            collection,
+            token_id,
+            registration_years(None)?,
+        )?),
+    };
+    COLLECTION_MINT_COUNTS.update(storage, collection, |total_minted| -> StdResult<_> {
+        Ok(total_minted.unwrap_or_default() + 1)
+    })?;
+    ADDRESS_MINT_COUNTS.update(storage, (collection, winner), |minted| -> StdResult<_> {
+        Ok(minted.unwrap_or_default() + 1)
+    })?;
+    Ok(CountedMint {
+        phase: None,
+        registration,
+        dutch_escrow: None,
+    })
+}
+
+fn release_mint(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    minter: &Addr,
+    counted_mint: &CountedMint,
+) -> StdResult<()> {
+    if let Some(phase) = &counted_mint.phase {
+        let key = (collection, phase.as_str(), minter);
+        let minted = PHASE_MINT_COUNTS
+            .may_load(storage, key)?
+            .unwrap_or_default();
+        PHASE_MINT_COUNTS.save(storage, key, &minted.saturating_sub(1))?;
+    }
+    if let Some(registration) = &counted_mint.registration {
+        remove_registration(storage, registration);
+    }
+    if let Some(dutch_escrow) = &counted_mint.dutch_escrow {
+        remove_dutch_escrow(storage, collection, minter, dutch_escrow)?;
+    }
+    let total_minted = COLLECTION_MINT_COUNTS
+        .may_load(storage, collection)?
+        .unwrap_or_default();
+    COLLECTION_MINT_COUNTS.save(storage, collection, &total_minted.saturating_sub(1))?;
+    let minted_by_address = ADDRESS_MINT_COUNTS
+        .may_load(storage, (collection, minter))?
+        .unwrap_or_default();
+    ADDRESS_MINT_COUNTS.save(
+        storage,
+        (collection, minter),
+        &minted_by_address.saturating_sub(1),
+    )
+}
+
+/// Registers the token id for the years paid for, when the collection prices token ids by the
+/// year.
+fn record_registration(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    collection: &Addr,
+    token_id: &str,
+    mint_options: Option<&MintOptions>,
+) -> Result<Option<Registration>, ContractError> {
+    if load_payment_params(storage, collection)?
+        .yearly_price
+        .is_none()
+    {
+        return Ok(None);
+    }
+    let years = registration_years(mint_options)?;
+    register_token(storage, now, collection, token_id, years).map(Some)
+}
+
+fn register_token(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    collection: &Addr,
+    token_id: &str,
+    years: u32,
+) -> Result<Registration, ContractError> {
+    if let Some(expires_at) = REGISTRATIONS.may_load(storage, (collection, token_id))? {
+        return Err(ContractError::AlreadyRegistered {
+            token_id: token_id.to_owned(),
+            expires_at,
+        });
+    }
+    let registration = Registration {
+        collection: collection.to_owned(),
+        token_id: token_id.to_owned(),
+        expires_at: registration_expiry(now, years),
+    };
+    save_registration(storage, &registration)?;
+    Ok(registration)
+}
+
+fn registration_years(mint_options: Option<&MintOptions>) -> Result<u32, ContractError> {
+    validate_registration_years(mint_options.and_then(|options| options.years).unwrap_or(1))
+}
+
+fn validate_registration_years(years: u32) -> Result<u32, ContractError> {
+    if years == 0 || MAX_REGISTRATION_YEARS < years {
+        return Err(ContractError::InvalidRegistrationYears {
+            max_years: MAX_REGISTRATION_YEARS,
+        });
+    }
+    Ok(years)
+}
+
+/// Expiries are kept in whole seconds, which is how they are indexed.
+fn registration_expiry(from: Timestamp, years: u32) -> Timestamp {
+    Timestamp::from_seconds(from.seconds() + u64::from(years) * SECONDS_PER_YEAR)
+}
+
+fn save_registration(storage: &mut dyn Storage, registration: &Registration) -> StdResult<()> {
+    let key = (&registration.collection, registration.token_id.as_str());
+    REGISTRATIONS.save(storage, key, &registration.expires_at)?;
+    REGISTRATION_EXPIRIES.save(
+        storage,
+        (
+            registration.expires_at.seconds(),
+            &registration.collection,
+            &registration.token_id,
+        ),
+        &Empty {},
+    )
+}
+
+fn remove_registration(storage: &mut dyn Storage, registration: &Registration) {
+    REGISTRATIONS.remove(
+        storage,
+        (&registration.collection, registration.token_id.as_str()),
+    );
+    REGISTRATION_EXPIRIES.remove(
+        storage,
+        (
+            registration.expires_at.seconds(),
+            &registration.collection,
+            &registration.token_id,
+        ),
+    );
+}
+
+fn load_registration(
+    storage: &dyn Storage,
+    collection: &Addr,
+    token_id: String,
+) -> Result<Registration, ContractError> {
+    match REGISTRATIONS.may_load(storage, (collection, &token_id))? {
+        None => Err(ContractError::NoRegistration { token_id }),
+        Some(expires_at) => Ok(Registration {
+            collection: collection.to_owned(),
+            token_id,
+            expires_at,
+        }),
+    }
+}
+
+fn execute_renew(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    token_id: String,
+    years: u32,
+) -> ContractResult {
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    let years = validate_registration_years(years)?;
+    let registration = load_registration(deps.storage, &collection_addr, token_id)?;
+    if registration.expires_at <= env.block.time {
+        return Err(ContractError::RegistrationExpired {
+            token_id: registration.token_id,
+            expires_at: registration.expires_at,
+        });
+    }
+    let payment_params = load_payment_params(deps.storage, &collection_addr)?;
+    if payment_params.yearly_price.is_none() {
+        return Err(ContractError::RenewalNotPriced { collection });
+    }
+    let renewal_prices = payment_params.registration_prices(vec![], years)?;
+    let (payment, refund) = pick_mint_payment(&renewal_prices, &info.funds)?;
+    remove_registration(deps.storage, &registration);
+    let renewed = Registration {
+        expires_at: registration_expiry(registration.expires_at, years),
+        ..registration
+    };
+    save_registration(deps.storage, &renewed)?;
+    let mut renew_event = Event::new("my-collection-manager")
+        .add_attribute("renew", collection)
+        .add_attribute("renew-token-id", renewed.token_id)
+        .add_attribute("renew-expires-at", renewed.expires_at.to_string());
+    let mut response = Response::default();
+    if let Some(payment) = payment {
+        renew_event = renew_event.add_attribute("renew-payment", payment.to_string());
+        response = response.add_messages(pay_out(
+            deps.storage,
+            &collection_addr,
+            split_payouts(&payment_params, &payment),
+        )?);
+    }
+    if !refund.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refund,
+        });
+    }
+    Ok(response.add_event(renew_event))
+}
+
+fn execute_reclaim(
+    deps: DepsMut,
+    env: Env,
+    collection: String,
+    token_id: String,
+    reassign: Option<Reassignment>,
+) -> ContractResult {
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    let registration = load_registration(deps.storage, &collection_addr, token_id)?;
+    if env.block.time < registration.expires_at {
+        return Err(ContractError::RegistrationNotExpired {
+            token_id: registration.token_id,
+            expires_at: registration.expires_at,
+        });
+    }
+    remove_registration(deps.storage, &registration);
+    let burn_msg = WasmMsg::Execute {
+        contract_addr: collection.to_owned(),
+        msg: to_json_binary(&CollectionExecuteMsg::Burn {
+            token_id: registration.token_id.to_owned(),
+        })?,
+        funds: vec![],
+    };
+    let reclaim_event = Event::new("my-collection-manager")
+        .add_attribute("reclaim", collection)
+        .add_attribute("reclaim-token-id", registration.token_id.to_owned());
+    let Some(reassign) = reassign else {
+        let reply_id = save_reply_context(
+            deps.storage,
+            ReplyCode::Reclaim,
+            &ReplyContext::Reclaim {
+                registration,
+                reassign_to: None,
+            },
+        )?;
+        return Ok(Response::default()
+            .add_submessage(SubMsg::reply_on_error(burn_msg, reply_id))
+            .add_event(reclaim_event));
+    };
+    let years = validate_registration_years(reassign.years)?;
+    let owner = deps.api.addr_validate(&reassign.owner)?;
+    let reassigned = Registration {
+        expires_at: registration_expiry(env.block.time, years),
+        ..registration
+    };
+    save_registration(deps.storage, &reassigned)?;
+    let reclaim_event = reclaim_event
+        .add_attribute("reclaim-reassign-to", owner.to_owned())
+        .add_attribute("reclaim-expires-at", reassigned.expires_at.to_string());
+    let reply_id = save_reply_context(
+        deps.storage,
+        ReplyCode::Reclaim,
+        &ReplyContext::Reclaim {
+            registration: reassigned,
+            reassign_to: Some(owner),
+        },
+    )?;
+    Ok(Response::default()
+        .add_submessage(SubMsg::reply_always(burn_msg, reply_id))
+        .add_event(reclaim_event))
+}
+
+fn execute_open_auction(
+    deps: DepsMut,
+    env: Env,
+    collection: String,
+    token_id: String,
+    reserve_price: Coin,
+    end: Timestamp,
+    min_increment: Uint128,
+) -> ContractResult {
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    let token_id = normalize_token_id(deps.storage, &collection_addr, &token_id)?;
+    if reserve_price.amount.is_zero() {
+        return Err(ContractError::ZeroPrice);
+    }
+    if end <= env.block.time {
+        return Err(ContractError::InvalidAuctionEnd);
+    }
+    if min_increment.is_zero() {
+        return Err(ContractError::ZeroBidIncrement);
+    }
+    if AUCTIONED_TOKENS.has(deps.storage, (&collection_addr, &token_id)) {
+        return Err(ContractError::AuctionExists { token_id });
+    }
+    if let Some(expires_at) = REGISTRATIONS.may_load(deps.storage, (&collection_addr, &token_id))? {
+        return Err(ContractError::AlreadyRegistered {
+            token_id,
+            expires_at,
+        });
+    }
+    if let Some(max_supply) = reached_max_supply(deps.storage, &collection_addr)? {
+        return Err(ContractError::MaxSupplyReached { max_supply });
+    }
+    let registration_price = match load_payment_params(deps.storage, &collection_addr)?.yearly_price
+    {
+        None => None,
+        Some(yearly_price) if yearly_price.denom == reserve_price.denom => {
+            Some(yearly_price.amount)
+        }
+        Some(_) => {
+            return Err(ContractError::YearlyPriceDenomMismatch {
+                denom: reserve_price.denom,
+            })
+        }
+    };
+    let auction_id = AUCTION_SEQUENCE.may_load(deps.storage)?.unwrap_or_default() + 1;
+    AUCTION_SEQUENCE.save(deps.storage, &auction_id)?;
+    AUCTIONED_TOKENS.save(deps.storage, (&collection_addr, &token_id), &auction_id)?;
+    let open_event = Event::new("my-collection-manager")
+        .add_attribute("open-auction", auction_id.to_string())
+        .add_attribute("open-auction-collection", collection_addr.to_owned())
+        .add_attribute("open-auction-token-id", token_id.to_owned())
+        .add_attribute("open-auction-reserve-price", reserve_price.to_string())
+        .add_attribute("open-auction-end", end.to_string())
+        .add_attribute("open-auction-min-increment", min_increment.to_string());
+    let open_event = match registration_price {
+        None => open_event,
+        Some(registration_price) => open_event.add_attribute(
+            "open-auction-registration-price",
+            registration_price.to_string(),
+        ),
+    };
+    AUCTIONS.save(
+        deps.storage,
+        auction_id,
+        &Auction {
+            id: auction_id,
+            collection: collection_addr,
+            token_id,
+            reserve_price,
+            end,
+            min_increment,
+            registration_price,
+            highest_bid: None,
+            bid_count: 0,
+        },
+    )?;
+    Ok(Response::default().add_event(open_event))
+}
+
+fn execute_bid(deps: DepsMut, env: Env, info: MessageInfo, auction_id: u64) -> ContractResult {
+    let mut auction = AUCTIONS
+        .may_load(deps.storage, auction_id)?
+        .ok_or(ContractError::UnknownAuction { auction_id })?;
+    if auction.end <= env.block.time {
+        return Err(ContractError::AuctionEnded { auction_id });
+    }
+    let denom = auction.reserve_price.denom.to_owned();
+    let registration_price = auction.registration_price.unwrap_or_default();
+    let paid = match info.funds.as_slice() {
+        [paid] if paid.denom == denom => paid.amount,
+        _ => return Err(ContractError::InvalidBidFunds { denom }),
+    };
+    let min_bid = match &auction.highest_bid {
+        None => auction.reserve_price.amount,
+        Some(highest_bid) => highest_bid.amount.strict_add(auction.min_increment),
+    };
+    let amount = match paid.checked_sub(registration_price) {
+        Ok(amount) if min_bid <= amount => amount,
+        _ => {
+            return Err(ContractError::BidTooLow {
+                min_bid: Coin {
+                    denom,
+                    amount: min_bid.strict_add(registration_price),
+                },
+            })
+        }
+    };
+    let bid = Bid {
+        bidder: info.sender.to_owned(),
+        amount,
+        placed_at: env.block.time,
+    };
+    AUCTION_BIDS.save(deps.storage, (auction_id, auction.bid_count), &bid)?;
+    let outbid = auction.highest_bid.replace(bid);
+    auction.bid_count += 1;
+    AUCTIONS.save(deps.storage, auction_id, &auction)?;
+    let mut bid_event = Event::new("my-collection-manager")
+        .add_attribute("bid", auction_id.to_string())
+        .add_attribute("bid-bidder", info.sender)
+        .add_attribute("bid-amount", amount.to_string());
+    let mut response = Response::default();
+    if let Some(outbid) = outbid {
+        let refund = Coin {
+            denom,
+            amount: outbid.amount.strict_add(registration_price),
+        };
+        bid_event = bid_event
+            .add_attribute("bid-outbid", outbid.bidder.to_owned())
+            .add_attribute("bid-outbid-refund", refund.to_string());
+        response = response.add_message(BankMsg::Send {
+            to_address: outbid.bidder.to_string(),
+            amount: vec![refund],
+        });
+    }
+    Ok(response.add_event(bid_event))
+}
+
+/// Settlements always come back to the reply, so that a mint the collection rejects refunds the
+/// winner instead of leaving the auction unsettled.
+fn execute_settle_auction(deps: DepsMut, env: Env, auction_id: u64) -> ContractResult {
+    let auction = AUCTIONS
+        .may_load(deps.storage, auction_id)?
+        .ok_or(ContractError::UnknownAuction { auction_id })?;
+    if env.block.time < auction.end {
+        return Err(ContractError::AuctionNotEnded { end: auction.end });
+    }
+    AUCTIONS.remove(deps.storage, auction_id);
+    AUCTIONED_TOKENS.remove(deps.storage, (&auction.collection, &auction.token_id));
+    let settle_event =
+        Event::new("my-collection-manager").add_attribute("settle-auction", auction_id.to_string());
+    let Some(winning_bid) = auction.highest_bid else {
+        return Ok(Response::default()
+            .add_event(settle_event.add_attribute("settle-auction-winner", "none")));
+    };
+    let mint_msg = CollectionExecuteMsg::Mint {
+        token_id: auction.token_id.to_owned(),
+        owner: winning_bid.bidder.to_string(),
+        token_uri: None,
+        extension: None,
+    };
+    assert_not_paused(deps.storage, &auction.collection, &mint_msg)?;
+    let payment = Coin {
+        denom: auction.reserve_price.denom,
+        amount: winning_bid
+            .amount
+            .strict_add(auction.registration_price.unwrap_or_default()),
+    };
+    let settle_event = settle_event.add_attribute("settle-auction-winner", &winning_bid.bidder);
+    // Past the deadline the auction has to close, so a mint that cannot happen refunds the winner.
+    let refund_winner = |settle_event: Event| {
+        Ok(Response::default()
+            .add_message(BankMsg::Send {
+                to_address: winning_bid.bidder.to_string(),
+                amount: vec![payment.to_owned()],
+            })
+            .add_event(settle_event.add_attribute("settle-auction-refund", payment.to_string())))
+    };
+    if let Some(max_supply) = reached_max_supply(deps.storage, &auction.collection)? {
+        return refund_winner(
+            settle_event.add_attribute("settle-auction-max-supply", max_supply.to_string()),
+        );
+    }
+    let counted_mint = match record_auction_mint(
+        deps.storage,
+        env.block.time,
+        &auction.collection,
+        &winning_bid.bidder,
+        &auction.token_id,
+        auction.registration_price.is_some(),
+    ) {
+        Ok(counted_mint) => counted_mint,
+        Err(err) => {
+            return refund_winner(
+                settle_event.add_attribute("settle-auction-error", err.to_string()),
+            )
+        }
+    };
+    let payment_params = load_payment_params(deps.storage, &auction.collection)?;
+    let held_payouts = split_payouts(&payment_params, &payment);
+    let reply_id = save_reply_context(
+        deps.storage,
+        ReplyCode::PassThrough,
+        &ReplyContext::PassThrough(PassThroughContext {
+            sender: winning_bid.bidder.to_owned(),
+            collection: auction.collection.to_owned(),
+            token_id: Some(auction.token_id.to_owned()),
+            payment: Some(payment.to_owned()),
+            counted_mint: Some(counted_mint.to_owned()),
+            held_payouts,
+            held_rebate: vec![],
+        }),
+    )?;
+    match forward_to_collection(
+        deps.as_ref(),
+        Response::default().add_event(settle_event.to_owned()),
+        auction.collection.to_string(),
+        mint_msg,
+        Some(payment.to_string()),
+        reply_id,
+        ReplyOn::Always,
+    ) {
+        Ok(response) => Ok(response),
+        Err(err) => {
+            REPLY_CONTEXTS.remove(deps.storage, reply_id);
+            release_mint(
+                deps.storage,
+                &auction.collection,
+                &winning_bid.bidder,
+                &counted_mint,
+            )?;
+            refund_winner(settle_event.add_attribute("settle-auction-error", err.to_string()))
+        }
+    }
+}
+
+/// Checks the minter against the active mint phase of the collection, when one is active, and
+/// counts the mint against its allowance. Returns the name of that phase. Mints outside every
+/// phase window are left to the payment params.
+fn record_phase_mint(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    collection: &Addr,
+    minter: &Addr,
+    mint_options: Option<&MintOptions>,
+) -> Result<Option<String>, ContractError> {
+    let phases = match MINT_PHASES.may_load(storage, collection)? {
+        None => return Ok(None),
+        Some(phases) => phases,
+    };
+    let Some(phase) = find_active_mint_phase(&phases, now) else {
+        return Ok(None);
+    };
+    if let Some(merkle_root) = &phase.merkle_root {
+        let merkle_proof = mint_options.map_or(&[][..], |options| &options.merkle_proof);
+        if !verify_merkle_proof(merkle_root, minter.as_bytes(), merkle_proof) {
+            return Err(ContractError::InvalidMerkleProof {
+                phase: phase.name.to_owned(),
+            });
+        }
+    }
+    let key = (collection, phase.name.as_str(), minter);
+    let minted = PHASE_MINT_COUNTS
+        .may_load(storage, key)?
+        .unwrap_or_default();
+    if let Some(limit) = phase.per_address_limit {
+        if limit <= minted {
+            return Err(ContractError::MintPhaseLimitReached {
+                phase: phase.name.to_owned(),
+                limit,
+            });
+        }
+    }
+    PHASE_MINT_COUNTS.save(storage, key, &(minted + 1))?;
+    Ok(Some(phase.name.to_owned()))
+}
+
+fn find_active_mint_phase(phases: &[MintPhase], now: Timestamp) -> Option<&MintPhase> {
+    phases
+        .iter()
+        .find(|phase| phase.start <= now && now < phase.end)
+}
+
+/// Leaves are the sha256 of the address, and each pair of nodes is hashed in ascending order so
+/// that proofs need not say which side each sibling is on.
+fn verify_merkle_proof(merkle_root: &HexBinary, leaf: &[u8], merkle_proof: &[HexBinary]) -> bool {
+    let root = merkle_proof
+        .iter()
+        .fold(Sha256::digest(leaf).to_vec(), |node, sibling| {
+            let (first, second) = if node.as_slice() <= sibling.as_slice() {
+                (node.as_slice(), sibling.as_slice())
+            } else {
+                (sibling.as_slice(), node.as_slice())
+            };
+            Sha256::new()
+                .chain_update(first)
+                .chain_update(second)
+                .finalize()
+                .to_vec()
+        });
+    root.as_slice() == merkle_root.as_slice()
+}
+
+fn execute_batch_pass_through(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    items: Vec<PassThroughItem>,
+    mode: BatchMode,
+) -> ContractResult {
+    let max_batch_size = CONFIG
+        .load(deps.storage)?
+        .max_batch_size
+        .unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+    if items.is_empty() {
+        return Err(ContractError::EmptyBatch);
+    }
+    if items.len() > max_batch_size as usize {
+        return Err(ContractError::BatchTooLarge { max_batch_size });
+    }
+    if items
+        .iter()
+        .any(|item| matches!(item.message, CollectionExecuteMsg::Mint { .. }))
+    {
+        assert_direct_mint_allowed(deps.storage)?;
+    }
+    let mut batch_event =
+        Event::new("my-collection-manager").add_attribute("batch-size", items.len().to_string());
+    // Items that fail the checks before their funds are planned are left out of the batch, and
+    // out of the pricing of the mints that go through with it.
+    let mut indices = Vec::with_capacity(items.len());
+    let mut checked_items = Vec::with_capacity(items.len());
+    let mut counted_mints = Vec::with_capacity(items.len());
+    for (index, mut item) in items.into_iter().enumerate() {
+        match (
+            record_batch_item(&mut deps, &env, &info.sender, &mut item),
+            &mode,
+        ) {
+            (Ok(counted_mint), _) => {
+                indices.push(index);
+                checked_items.push(item);
+                counted_mints.push(counted_mint);
+            }
+            (Err(err), BatchMode::Atomic) => return Err(err),
+            (Err(err), BatchMode::BestEffort) => {
+                batch_event =
+                    batch_event.add_attribute("batch-item-failed", format!("{}:{}", index, err));
+            }
+        }
+    }
+    let items = checked_items;
+    let mut response = Response::default();
+    let mut remaining_funds = info.funds.to_owned();
+    let mut paid_denoms = Vec::<(String, Option<String>)>::new();
+    for (position, item) in items.iter().enumerate() {
+        let index = indices[position];
+        let planned = plan_batch_item(
+            deps.as_ref(),
+            env.block.time,
+            &info.sender,
+            &items,
+            position,
+            &mut paid_denoms,
+            &mut remaining_funds,
+        );
+        let planned = match (planned, &mode) {
+            (Ok(planned), _) => planned,
+            (Err(err), BatchMode::Atomic) => return Err(err),
+            (Err(err), BatchMode::BestEffort) => {
+                if let Some(counted_mint) = &counted_mints[position] {
+                    release_mint(
+                        deps.storage,
+                        &deps.api.addr_validate(&item.collection)?,
+                        &info.sender,
+                        counted_mint,
+                    )?;
+                }
+                batch_event =
+                    batch_event.add_attribute("batch-item-failed", format!("{}:{}", index, err));
+                continue;
+            }
+        };
+        draw_rebate(
+            deps.storage,
+            &deps.api.addr_validate(&item.collection)?,
+            &planned.rebate,
+        )?;
+        if let Some(group_payment) = planned.group_payment {
+            batch_event = batch_event.add_attribute("mint-payment", group_payment.to_string());
+        }
+        if let (Some(counted_mint), Some(dutch_escrow)) =
+            (&mut counted_mints[position], planned.dutch_escrow)
+        {
+            add_dutch_escrow(
+                deps.storage,
+                &deps.api.addr_validate(&item.collection)?,
+                &info.sender,
+                &dutch_escrow,
+            )?;
+            counted_mint.dutch_escrow = Some(dutch_escrow);
+        }
+        let onward_exec_msg = WasmMsg::Execute {
+            contract_addr: item.collection.to_owned(),
+            msg: to_json_binary(&item.message)?,
+            funds: vec![],
+        };
+        response = match mode {
+            BatchMode::Atomic => response
+                .add_messages(pay_out(
+                    deps.storage,
+                    &deps.api.addr_validate(&item.collection)?,
+                    planned.payouts,
+                )?)
+                .add_messages(pay_or_accrue(deps.storage, planned.rebate)?)
+                .add_message(onward_exec_msg),
+            BatchMode::BestEffort => {
+                let reply_id = save_reply_context(
+                    deps.storage,
+                    ReplyCode::BatchItem,
+                    &ReplyContext::BatchItem {
+                        index: index as u32,
+                        pass_through: PassThroughContext {
+                            sender: info.sender.to_owned(),
+                            collection: deps.api.addr_validate(&item.collection)?,
+                            token_id: message_token_id(&item.message),
+                            payment: planned.payment,
+                            counted_mint: counted_mints[position].to_owned(),
+                            held_payouts: planned.payouts,
+                            held_rebate: planned.rebate,
+                        },
+                    },
+                )?;
+                response.add_submessage(SubMsg::reply_always(onward_exec_msg, reply_id))
+            }
+        };
+    }
+    if !remaining_funds.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: remaining_funds,
+        });
+    }
+    Ok(response.add_event(batch_event))
+}
+
+/// Checks one batch item before its funds are planned, and counts it when it is a mint. Items on
+/// unregistered collections are left for the planning to reject.
+fn record_batch_item(
+    deps: &mut DepsMut,
+    env: &Env,
+    sender: &Addr,
+    item: &mut PassThroughItem,
+) -> Result<Option<CountedMint>, ContractError> {
+    let Ok(collection_addr) = assert_registered_collection(deps.as_ref(), &item.collection) else {
+        return Ok(None);
+    };
+    assert_not_paused(deps.storage, &collection_addr, &item.message)?;
+    assert_pass_through_allowed(deps.storage, &collection_addr, sender, &item.message)?;
+    assert_token_owner(deps.as_ref(), &collection_addr, sender, &item.message)?;
+    if !matches!(item.message, CollectionExecuteMsg::Mint { .. }) {
+        return Ok(None);
+    }
+    apply_token_id_policy(deps.storage, &collection_addr, &mut item.message)?;
+    let counted_mint = record_mint(
+        deps.storage,
+        env.block.time,
+        &collection_addr,
+        sender,
+        &message_token_id(&item.message).unwrap_or_default(),
+        item.mint_options.as_ref(),
+    )?;
+    Ok(Some(counted_mint))
+}
+
+struct BatchItemPlan {
+    payment: Option<Coin>,
+    payouts: Vec<MintPayout>,
+    rebate: Vec<MintPayout>,
+    /// Set on the first mint of a collection, which pays for all the mints on it.
+    group_payment: Option<Coin>,
+    dutch_escrow: Option<DutchEscrow>,
+}
+
+/// Plans the funds of one batch item. Mints are priced once per collection, for all the mints
+/// of the batch on that collection, the first time one of them comes up.
+fn plan_batch_item(
+    deps: Deps,
+    now: Timestamp,
+    sender: &Addr,
+    items: &[PassThroughItem],
+    index: usize,
+    paid_denoms: &mut Vec<(String, Option<String>)>,
+    remaining_funds: &mut Vec<Coin>,
+) -> Result<BatchItemPlan, ContractError> {
+    let item = &items[index];
+    let collection_addr = assert_registered_collection(deps, &item.collection)?;
+    let CollectionExecuteMsg::Mint { token_id, .. } = &item.message else {
+        let plan = plan_action_fee(
+            deps,
+            &collection_addr,
+            sender,
+            &CollectionAction::of(&item.message),
+            remaining_funds,
+        )?;
+        *remaining_funds = plan.refund;
+        return Ok(BatchItemPlan {
+            payment: plan.payment,
+            payouts: plan.payouts,
+            rebate: plan.rebate,
+            group_payment: None,
+            dutch_escrow: None,
+        });
+    };
+    let payment_params = load_mint_payment_params(deps.storage, &collection_addr, now)?;
+    let supply = bonding_curve_supply(deps, &collection_addr, &payment_params)?;
+    let (paid_denom, group_payment) = match paid_denoms
+        .iter()
+        .find(|(collection, _)| collection == &item.collection)
+    {
+        Some((_, paid_denom)) => (paid_denom.to_owned(), None),
+        None => {
+            let mint_prices = items
+                .iter()
+                .filter(|other| other.collection == item.collection)
+                .filter_map(|other| match &other.message {
+                    CollectionExecuteMsg::Mint { token_id, .. } => Some((token_id, other)),
+                    _ => None,
+                })
+                .zip(supply..)
+                .map(|((token_id, other), supply)| {
+                    registration_mint_prices(
+                        &supply_payment_params(&payment_params, supply)?,
+                        token_id,
+                        other.mint_options.as_ref(),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter(|prices| !prices.is_empty())
+                .collect::<Vec<_>>();
+            let group_prices = total_mint_prices(&mint_prices)?;
+            if !mint_prices.is_empty() && group_prices.is_empty() {
+                return Err(ContractError::BatchMintDenomMismatch {
+                    collection: item.collection.to_owned(),
+                });
+            }
+            let (group_payment, change) = pick_mint_payment(&group_prices, remaining_funds)?;
+            *remaining_funds = change;
+            let paid_denom = group_payment.as_ref().map(|paid| paid.denom.to_owned());
+            paid_denoms.push((item.collection.to_owned(), paid_denom.to_owned()));
+            (paid_denom, group_payment)
+        }
+    };
+    // Mints on a bonding curve are priced by the supply the mints before them in the batch reach.
+    let minted_before = items[..index]
+        .iter()
+        .filter(|other| {
+            other.collection == item.collection
+                && matches!(other.message, CollectionExecuteMsg::Mint { .. })
+        })
+        .count() as u64;
+    let payment_params = supply_payment_params(&payment_params, supply + minted_before)?;
+    let unit_price = match paid_denom {
+        None => None,
+        Some(paid_denom) => {
+            registration_mint_prices(&payment_params, token_id, item.mint_options.as_ref())?
+                .into_iter()
+                .find(|price| price.denom == paid_denom)
+        }
+    };
+    let (payouts, dutch_escrow) = match &unit_price {
+        None => (vec![], None),
+        Some(paid) => mint_payouts(&payment_params, paid),
+    };
+    Ok(BatchItemPlan {
+        payment: unit_price,
+        payouts,
+        rebate: vec![],
+        group_payment,
+        dutch_escrow,
+    })
+}
+
+/// The native prices of minting `token_id`, with the registration the mint pays for.
+fn registration_mint_prices(
+    payment_params: &PaymentParams,
+    token_id: &str,
+    mint_options: Option<&MintOptions>,
+) -> Result<Vec<Coin>, ContractError> {
+    payment_params.registration_prices(
+        payment_params.mint_prices_for(token_id)?,
+        registration_years(mint_options)?,
+    )
+}
+
+/// Totals the prices of several mints, in each of the denoms that all of them accept.
+fn total_mint_prices(mint_prices: &[Vec<Coin>]) -> StdResult<Vec<Coin>> {
+    let mut totals = mint_prices.first().cloned().unwrap_or_default();
+    for prices in mint_prices.iter().skip(1) {
+        totals = totals
+            .into_iter()
+            .filter_map(|total| {
+                prices
+                    .iter()
+                    .find(|price| price.denom == total.denom)
+                    .map(|price| {
+                        Ok(Coin {
+                            amount: total.amount.checked_add(price.amount)?,
+                            denom: total.denom,
+                        })
+                    })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+    }
+    Ok(totals)
+}
+
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive_msg: Cw20ReceiveMsg,
+) -> ContractResult {
+    let token = info.sender;
+    if !ACCEPTED_CW20.has(deps.storage, &token) {
+        return Err(ContractError::UnacceptedCw20 {
+            token: token.to_string(),
+        });
+    }
+    let sender = deps.api.addr_validate(&receive_msg.sender)?;
+    let ReceiveMsg::PassThrough {
+        collection,
+        mut message,
+        mint_options,
+    } = from_json(&receive_msg.msg)?;
+    if !matches!(message, CollectionExecuteMsg::Mint { .. }) {
+        return Err(ContractError::Cw20PassThroughNotMint);
+    }
+    assert_direct_mint_allowed(deps.storage)?;
+    let collection_addr = assert_registered_collection(deps.as_ref(), &collection)?;
+    assert_not_paused(deps.storage, &collection_addr, &message)?;
+    assert_pass_through_allowed(deps.storage, &collection_addr, &sender, &message)?;
+    apply_token_id_policy(deps.storage, &collection_addr, &mut message)?;
+    let counted_mint = record_mint(
+        deps.storage,
+        env.block.time,
+        &collection_addr,
+        &sender,
+        &message_token_id(&message).unwrap_or_default(),
+        mint_options.as_ref(),
+    )?;
+    let payment_params = load_payment_params(deps.storage, &collection_addr)?;
+    let price = match payment_params
+        .cw20_mint_prices
+        .iter()
+        .find(|price| price.address == token)
+    {
+        Some(price) if price.amount.le(&receive_msg.amount) => price.amount,
+        _ => {
+            return Err(ContractError::MissingCw20Payment {
+                accepted_payments: payment_params.cw20_mint_prices,
+            })
+        }
+    };
+    let reply_id = save_reply_context(
+        deps.storage,
+        ReplyCode::PassThrough,
+        &ReplyContext::PassThrough(PassThroughContext {
+            sender: sender.to_owned(),
+            collection: collection_addr,
+            token_id: message_token_id(&message),
+            payment: None,
+            counted_mint: Some(counted_mint),
+            held_payouts: vec![],
+            held_rebate: vec![],
+        }),
+    )?;
+    // Cw20 revenue is always transferred right away. It never accrues in the manager and is not
+    // counted as revenue of the collection.
+    let mut transfer_msgs = split_payment(&payment_params, price)
+        .into_iter()
+        .map(|(recipient, amount)| cw20_transfer_msg(&token, &recipient, amount))
+        .collect::<StdResult<Vec<_>>>()?;
+    let change = receive_msg.amount.strict_sub(price);
+    if !change.is_zero() {
+        transfer_msgs.push(cw20_transfer_msg(&token, &sender, change)?);
+    }
+    forward_to_collection(
+        deps.as_ref(),
+        Response::default().add_messages(transfer_msgs),
+        collection,
+        message,
+        Some(format!("{}{}", price, token)),
+        reply_id,
+        ReplyOn::Success,
+    )
+}
+
+fn cw20_transfer_msg(token: &Addr, recipient: &Addr, amount: Uint128) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    })
+}
+
+fn assert_registered_collection(deps: Deps, collection: &str) -> Result<Addr, ContractError> {
+    let collection_addr = deps.api.addr_validate(collection)?;
+    if !COLLECTIONS.has(deps.storage, &collection_addr) {
+        return Err(ContractError::UnregisteredCollection {
+            collection: collection.to_owned(),
+        });
+    }
+    Ok(collection_addr)
+}
+
+fn forward_to_collection(
+    deps: Deps,
+    response: Response,
+    collection: String,
+    message: CollectionExecuteMsg,
+    payment: Option<String>,
+    reply_id: u64,
+    reply_on: ReplyOn,
+) -> ContractResult {
+    let onward_exec_msg = WasmMsg::Execute {
+        contract_addr: collection.to_owned(),
+        msg: to_json_binary(&message)?,
+        funds: vec![],
+    };
+    let onward_sub_msg = SubMsg {
+        id: reply_id,
+        msg: CosmosMsg::<Empty>::Wasm(onward_exec_msg),
+        reply_on,
+        gas_limit: None,
+    };
+    let token_count_event = Event::new("my-collection-manager").add_attribute(
+        "token-count-before",
+        query_token_count(deps, collection)?.to_string(),
+    );
+    let token_count_event = match payment {
+        None => token_count_event,
+        Some(payment) => token_count_event.add_attribute("mint-payment", payment),
+    };
+    Ok(response
+        .add_submessage(onward_sub_msg)
+        .add_event(token_count_event))
+}
+
+fn query_token_count(deps: Deps, collection: impl Into<String>) -> StdResult<u64> {
+    let num_tokens = deps
+        .querier
+        .query::<NumTokensResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: collection.into(),
+            msg: to_json_binary(&CollectionQueryMsg::NumTokens {})?,
+        }))?;
+    Ok(num_tokens.count)
+}
+
+/// The supply of the collection when it prices mints on a bonding curve, and zero otherwise.
+fn bonding_curve_supply(
+    deps: Deps,
+    collection: &Addr,
+    payment_params: &PaymentParams,
+) -> StdResult<u64> {
+    match payment_params.bonding_curve {
+        None => Ok(0),
+        Some(_) => query_token_count(deps, collection),
+    }
+}
+
+/// The payment params of the mint that comes after `supply` tokens.
+fn supply_payment_params(payment_params: &PaymentParams, supply: u64) -> StdResult<PaymentParams> {
+    let mut payment_params = payment_params.to_owned();
+    if let Some(bonding_curve) = &payment_params.bonding_curve {
+        payment_params.mint_price = Some(bonding_curve.price_at(supply)?);
+    }
+    Ok(payment_params)
+}
+
+struct MintFundsPlan {
+    payment: Option<Coin>,
+    payouts: Vec<MintPayout>,
+    refund: Vec<Coin>,
+    dutch_escrow: Option<DutchEscrow>,
+    /// Drawn from the rebate pool and paid out once the action goes through.
+    rebate: Vec<MintPayout>,
+}
+
+/// Refunds the change right away, and pays out the payment unless it is held until the reply.
+fn handle_pre_mint_funds(
+    deps: &mut DepsMut,
+    now: Timestamp,
+    info: &MessageInfo,
+    collection: &Addr,
+    token_id: &str,
+    mint_options: Option<&MintOptions>,
+    hold_payment: bool,
+) -> Result<(MintFundsPlan, Vec<BankMsg>), ContractError> {
+    let plan = plan_mint_funds(
+        deps.as_ref(),
+        now,
+        collection,
+        Some(token_id),
+        mint_options,
+        &info.funds,
+    )?;
+    let mut bank_msgs = if hold_payment {
+        vec![]
+    } else {
+        pay_out(deps.storage, collection, plan.payouts.to_owned())?
+    };
+    if !plan.refund.is_empty() {
+        bank_msgs.push(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: plan.refund.to_owned(),
+        })
+    };
+    Ok((plan, bank_msgs))
+}
+
+/// Pays out revenue of the collection, or credits it to the payees when revenue accrues in the
+/// manager.
+fn pay_out(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    payouts: Vec<MintPayout>,
+) -> StdResult<Vec<BankMsg>> {
+    if payouts.is_empty() {
+        return Ok(vec![]);
+    }
+    for payout in &payouts {
+        COLLECTION_REVENUE.update(
+            storage,
+            (collection, &payout.amount.denom),
+            |revenue| -> StdResult<_> {
+                Ok(revenue.unwrap_or_default().strict_add(payout.amount.amount))
+            },
+        )?;
+    }
+    pay_or_accrue(storage, payouts)
+}
+
+/// Sends the payouts right away, or credits them to the payees when revenue accrues in the
+/// manager.
+fn pay_or_accrue(storage: &mut dyn Storage, payouts: Vec<MintPayout>) -> StdResult<Vec<BankMsg>> {
+    if payouts.is_empty() {
+        return Ok(vec![]);
+    }
+    if !CONFIG.load(storage)?.accrue_revenue {
+        return Ok(payouts.into_iter().map(payout_msg).collect());
+    }
+    for payout in payouts {
+        CLAIMABLE_BALANCES.update(
+            storage,
+            (&payout.recipient, &payout.amount.denom),
+            |balance| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().strict_add(payout.amount.amount))
+            },
+        )?;
+    }
+    Ok(vec![])
+}
+
+fn payout_msg(payout: MintPayout) -> BankMsg {
+    BankMsg::Send {
+        to_address: payout.recipient.to_string(),
+        amount: vec![payout.amount],
+    }
+}
+
+/// Works out, without side effects, where the funds sent along a mint go.
+fn plan_mint_funds(
+    deps: Deps,
+    now: Timestamp,
+    collection: &Addr,
+    token_id: Option<&str>,
+    mint_options: Option<&MintOptions>,
+    funds: &[Coin],
+) -> Result<MintFundsPlan, ContractError> {
+    let payment_params = load_mint_payment_params(deps.storage, collection, now)?;
+    let supply = bonding_curve_supply(deps, collection, &payment_params)?;
+    let payment_params = supply_payment_params(&payment_params, supply)?;
+    let mint_prices = match token_id {
+        None => payment_params.accepted_mint_prices(),
+        Some(token_id) => payment_params.mint_prices_for(token_id)?,
+    };
+    let mint_prices =
+        payment_params.registration_prices(mint_prices, registration_years(mint_options)?)?;
+    let (payment, refund) = pick_mint_payment(&mint_prices, funds)?;
+    let (payouts, dutch_escrow) = match &payment {
+        None => (vec![], None),
+        Some(paid) => mint_payouts(&payment_params, paid),
+    };
+    Ok(MintFundsPlan {
+        payment,
+        payouts,
+        refund,
+        dutch_escrow,
+        rebate: vec![],
+    })
+}
+
+/// Splits a mint payment between the payees. When a dutch auction refunds down to the clearing
+/// price, the part of its price above the end price is held back instead.
+fn mint_payouts(
+    payment_params: &PaymentParams,
+    paid: &Coin,
+) -> (Vec<MintPayout>, Option<DutchEscrow>) {
+    let dutch_escrow = payment_params
+        .dutch_auction
+        .as_ref()
+        .filter(|dutch_auction| dutch_auction.refund_to_clearing_price)
+        .zip(payment_params.mint_price.as_ref())
+        .map(|(dutch_auction, price)| DutchEscrow {
+            start: dutch_auction.start,
+            ends_at: dutch_auction.ends_at(),
+            denom: price.denom.to_owned(),
+            minted: 1,
+            paid: price.amount,
+            held: price.amount - dutch_auction.end_price,
+        });
+    let held = dutch_escrow
+        .as_ref()
+        .map(|dutch_escrow| dutch_escrow.held)
+        .unwrap_or_default();
+    let payouts = split_payouts(
+        payment_params,
+        &Coin {
+            denom: paid.denom.to_owned(),
+            amount: paid.amount - held,
+        },
+    );
+    (payouts, dutch_escrow)
+}
+
+/// Adds a mint to the dutch auction escrow of the minter, and lowers the clearing price to what
+/// it paid.
+fn add_dutch_escrow(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    minter: &Addr,
+    dutch_escrow: &DutchEscrow,
+) -> StdResult<()> {
+    let key = (collection, minter, dutch_escrow.start.seconds());
+    let escrow = match DUTCH_ESCROWS.may_load(storage, key)? {
+        None => dutch_escrow.to_owned(),
+        Some(escrow) => DutchEscrow {
+            minted: escrow.minted + dutch_escrow.minted,
+            paid: escrow.paid + dutch_escrow.paid,
+            held: escrow.held + dutch_escrow.held,
+            ..escrow
+        },
+    };
+    DUTCH_ESCROWS.save(storage, key, &escrow)?;
+    let auction_key = (collection, dutch_escrow.start.seconds());
+    DUTCH_CLEARING_PRICES.update(storage, auction_key, |clearing_price| -> StdResult<_> {
+        Ok(clearing_price.map_or(dutch_escrow.paid, |clearing_price| {
+            clearing_price.min(dutch_escrow.paid)
+        }))
+    })?;
+    Ok(())
+}
+
+/// Takes a failed mint back out of the dutch auction escrow of the minter. The clearing price
+/// stays where the mint put it.
+fn remove_dutch_escrow(
+    storage: &mut dyn Storage,
+    collection: &Addr,
+    minter: &Addr,
+    dutch_escrow: &DutchEscrow,
+) -> StdResult<()> {
+    let key = (collection, minter, dutch_escrow.start.seconds());
+    let Some(escrow) = DUTCH_ESCROWS.may_load(storage, key)? else {
+        return Ok(());
+    };
+    let escrow = DutchEscrow {
+        minted: escrow.minted.saturating_sub(dutch_escrow.minted),
+        paid: escrow.paid.saturating_sub(dutch_escrow.paid),
+        held: escrow.held.saturating_sub(dutch_escrow.held),
+        ..escrow
+    };
+    if escrow.minted == 0 {
+        DUTCH_ESCROWS.remove(storage, key);
+        Ok(())
+    } else {
+        DUTCH_ESCROWS.save(storage, key, &escrow)
+    }
+}
+
+/// Works out, without side effects, where the funds sent along a non-mint action go. They are all
+/// refunded when the action has no fee or pays a rebate, which the rebate pool has to cover.
+fn plan_action_fee(
+    deps: Deps,
+    collection: &Addr,
+    sender: &Addr,
+    action: &CollectionAction,
+    funds: &[Coin],
+) -> Result<MintFundsPlan, ContractError> {
+    let payment_params = load_payment_params(deps.storage, collection)?;
+    let Some(fee) = payment_params
+        .action_fees
+        .iter()
+        .find(|fee| &fee.action == action)
+    else {
+        return Ok(MintFundsPlan {
+            payment: None,
+            payouts: vec![],
+            refund: funds.to_vec(),
+            dutch_escrow: None,
+            rebate: vec![],
+        });
+    };
+    if fee.rebate {
+        let pool = REBATE_POOLS
+            .may_load(deps.storage, (collection, &fee.price.denom))?
+            .unwrap_or_default();
+        if pool < fee.price.amount {
+            return Err(ContractError::RebatePoolTooLow {
+                collection: collection.to_string(),
+                amount: fee.price.to_owned(),
+            });
+        }
+        let rebate_params = PaymentParams {
+            beneficiary: sender.to_owned(),
+            revenue_split: fee.revenue_split.to_owned(),
+            ..payment_params.to_owned()
+        };
+        return Ok(MintFundsPlan {
+            payment: None,
+            payouts: vec![],
+            refund: funds.to_vec(),
+            dutch_escrow: None,
+            rebate: split_payouts(&rebate_params, &fee.price),
+        });
+    }
+    let (payment, refund) = pick_mint_payment(&[fee.price.to_owned()], funds)?;
+    let payouts = match &payment {
+        None => vec![],
+        Some(paid) if fee.revenue_split.is_empty() => split_payouts(&payment_params, paid),
+        Some(paid) => split_payouts(
+            &PaymentParams {
+                revenue_split: fee.revenue_split.to_owned(),
+                ..payment_params.to_owned()
+            },
+            paid,
+        ),
+    };
+    Ok(MintFundsPlan {
+        payment,
+        payouts,
+        refund,
+        dutch_escrow: None,
+        rebate: vec![],
+    })
+}
+
+/// Picks the first accepted price that the funds cover, and returns it with the change.
+fn pick_mint_payment(
+    accepted_prices: &[Coin],
+    funds: &[Coin],
+) -> Result<(Option<Coin>, Vec<Coin>), ContractError> {
+    if accepted_prices.is_empty() {
+        return Ok((None, funds.to_vec()));
+    }
+    for minting_price in accepted_prices {
+        if minting_price.amount.le(&Uint128::zero()) {
+            Err(ContractError::ZeroPrice)?
+        }
+        let (aggregated, mut others) = split_fund_denom(&minting_price.denom, funds);
+        match aggregated.checked_sub(minting_price.amount) {
+            Err(_) => continue,
+            Ok(change_in_denom) if change_in_denom.le(&Uint128::zero()) => {}
+            Ok(change_in_denom) => others.push(Coin {
+                denom: minting_price.denom.clone(),
+                amount: change_in_denom,
+            }),
+        };
+        return Ok((Some(minting_price.to_owned()), others));
+    }
+    Err(ContractError::MissingPayment {
+        accepted_payments: accepted_prices.to_vec(),
+    })
+}
+
+fn split_payouts(payment_params: &PaymentParams, payment: &Coin) -> Vec<MintPayout> {
+    split_payment(payment_params, payment.amount)
+        .into_iter()
+        .map(|(recipient, amount)| MintPayout {
+            recipient,
+            amount: Coin {
+                denom: payment.denom.to_owned(),
+                amount,
+            },
+        })
+        .collect()
+}
+
+/// Divides the payment between the revenue shares by weight, rounding down, and gives the
+/// rounding dust to the beneficiary.
+fn split_payment(payment_params: &PaymentParams, payment: Uint128) -> Vec<(Addr, Uint128)> {
+    if payment_params.revenue_split.is_empty() {
+        return vec![(payment_params.beneficiary.to_owned(), payment)];
+    }
+    let total_weight = payment_params.total_weight();
+    let mut payouts = payment_params
+        .revenue_split
+        .iter()
+        .map(|share| {
+            (
+                share.recipient.to_owned(),
+                payment.multiply_ratio(share.weight, total_weight),
+            )
+        })
+        .collect::<Vec<_>>();
+    let distributed = payouts
+        .iter()
+        .fold(Uint128::zero(), |sum, (_, amount)| sum.strict_add(*amount));
+    let dust = payment.strict_sub(distributed);
+    if !dust.is_zero() {
+        match payouts
+            .iter_mut()
+            .find(|(recipient, _)| recipient == &payment_params.beneficiary)
+        {
+            Some((_, amount)) => *amount = amount.strict_add(dust),
+            None => payouts.push((payment_params.beneficiary.to_owned(), dust)),
+        }
+    }
+    payouts
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .collect()
+}
+
+fn load_payment_params(storage: &dyn Storage, collection: &Addr) -> StdResult<PaymentParams> {
+    match COLLECTION_PAYMENT_PARAMS.may_load(storage, collection)? {
+        Some(payment_params) => Ok(payment_params),
+        None => PAYMENT_PARAMS.load(storage),
+    }
+}
+
+/// The payment params of the collection, with the mint price of its active mint phase, if any.
+fn load_mint_payment_params(
+    storage: &dyn Storage,
+    collection: &Addr,
+    now: Timestamp,
+) -> StdResult<PaymentParams> {
+    let mut payment_params = load_payment_params(storage, collection)?;
+    if let Some(dutch_auction) = &payment_params.dutch_auction {
+        payment_params.mint_price = Some(dutch_auction.price_at(now));
+    }
+    let phases = MINT_PHASES
+        .may_load(storage, collection)?
+        .unwrap_or_default();
+    if let Some(mint_price) =
+        find_active_mint_phase(&phases, now).and_then(|phase| phase.mint_price.to_owned())
+    {
+        payment_params.mint_price = Some(mint_price);
+        payment_params.dutch_auction = None;
+        payment_params.bonding_curve = None;
+    }
+    Ok(payment_params)
+}
+
+fn split_fund_denom(denom: &String, funds: &[Coin]) -> (Uint128, Vec<Coin>) {
+    let (amount, others) = funds.iter().fold(
+        (Uint128::zero(), Vec::with_capacity(funds.len())),
+        |(aggregated, mut others), fund| {
+            if &fund.denom == denom {
+                (aggregated.strict_add(fund.amount), others)
+            } else {
+                others.push(fund.clone());
+                (aggregated, others)
+            }
+        },
+    );
+    (amount, others)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> ContractResult {
+    let code = ReplyCode::try_from(msg.id)?;
+    let context = REPLY_CONTEXTS
+        .may_load(deps.storage, msg.id)?
+        .ok_or(ContractError::UnknownReplyId { id: msg.id })?;
+    REPLY_CONTEXTS.remove(deps.storage, msg.id);
+    match (code, context) {
+        (ReplyCode::PassThrough, ReplyContext::PassThrough(context)) => {
+            reply_pass_through(deps, env, msg, context)
+        }
+        (ReplyCode::CreateCollection, ReplyContext::CreateCollection(collection_info)) => {
+            reply_create_collection(deps, env, msg, collection_info)
+        }
+        (
+            ReplyCode::BatchItem,
+            ReplyContext::BatchItem {
+                index,
+                pass_through,
+            },
+        ) => reply_batch_item(deps, env, msg, index, pass_through),
+        (
+            ReplyCode::Reclaim,
+            ReplyContext::Reclaim {
+                registration,
+                reassign_to,
+            },
+        ) => reply_reclaim(deps, msg, registration, reassign_to),
+        _ => Err(ContractError::UnknownReplyId { id: msg.id }),
+    }
+}
+
+/// A failed burn leaves the token with its holder, but the registration stays dropped so that a
+/// revoked approval or a direct transfer on the collection cannot block the reclaim.
+fn reply_reclaim(
+    deps: DepsMut,
+    msg: Reply,
+    registration: Registration,
+    reassign_to: Option<Addr>,
+) -> ContractResult {
+    match (msg.result, reassign_to) {
+        (SubMsgResult::Ok(_), None) => Ok(Response::default()),
+        (SubMsgResult::Ok(_), Some(owner)) => {
+            let mint_msg = WasmMsg::Execute {
+                contract_addr: registration.collection.to_string(),
+                msg: to_json_binary(&CollectionExecuteMsg::Mint {
+                    token_id: registration.token_id,
+                    owner: owner.to_string(),
+                    token_uri: None,
+                    extension: None,
+                })?,
+                funds: vec![],
+            };
+            Ok(Response::default().add_message(mint_msg))
+        }
+        (SubMsgResult::Err(err), reassign_to) => {
+            if reassign_to.is_some() {
+                remove_registration(deps.storage, &registration);
+            }
+            let event = Event::new("my-collection-manager")
+                .add_attribute("reclaim-burn-failed", registration.token_id)
+                .add_attribute("reclaim-burn-error", err);
+            Ok(Response::default().add_event(event))
+        }
+    }
+}
+
+fn reply_batch_item(
+    deps: DepsMut,
+    _env: Env,
+    msg: Reply,
+    index: u32,
+    context: PassThroughContext,
+) -> ContractResult {
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            let mut bank_msgs = pay_out(deps.storage, &context.collection, context.held_payouts)?;
+            bank_msgs.extend(pay_or_accrue(deps.storage, context.held_rebate)?);
+            let event = Event::new("my-collection-manager")
+                .add_attribute("batch-item-succeeded", index.to_string());
+            Ok(Response::default().add_messages(bank_msgs).add_event(event))
+        }
+        SubMsgResult::Err(err) => {
+            if let Some(counted_mint) = &context.counted_mint {
+                release_mint(
+                    deps.storage,
+                    &context.collection,
+                    &context.sender,
+                    counted_mint,
+                )?;
+            }
+            refill_rebate_pool(deps.storage, &context.collection, &context.held_rebate)?;
+            let event = Event::new("my-collection-manager")
+                .add_attribute("batch-item-failed", format!("{}:{}", index, err));
+            let response = Response::default().add_event(event);
+            Ok(match context.payment {
+                None => response,
+                Some(payment) => response.add_message(BankMsg::Send {
+                    to_address: context.sender.to_string(),
+                    amount: vec![payment],
+                }),
+            })
+        }
+    }
+}
+
+fn reply_create_collection(
+    deps: DepsMut,
+    _env: Env,
+    msg: Reply,
+    collection_info: CollectionInfo,
+) -> ContractResult {
+    let instantiate_response = parse_reply_instantiate_data(msg)?;
+    let collection = deps
+        .api
+        .addr_validate(&instantiate_response.contract_address)?;
+    COLLECTIONS.save(deps.storage, &collection, &collection_info)?;
+    let event = Event::new("my-collection-manager")
+        .add_attribute("create-collection", collection)
+        .add_attribute("create-collection-label", collection_info.label)
+        .add_attribute(
+            "create-collection-code-id",
+            collection_info.code_id.to_string(),
+        );
+    Ok(Response::default().add_event(event))
+}
+
+fn reply_pass_through(
+    deps: DepsMut,
+    _env: Env,
+    msg: Reply,
+    context: PassThroughContext,
+) -> ContractResult {
+    let resp = match msg.result {
+        SubMsgResult::Err(err) => return refund_failed_mint(deps, context, err),
+        SubMsgResult::Ok(resp) => resp,
+    };
+    let response = Response::default()
+        .add_messages(pay_out(
+            deps.storage,
+            &context.collection,
+            context.held_payouts,
+        )?)
+        .add_messages(pay_or_accrue(deps.storage, context.held_rebate)?);
+    let data = match resp.data {
+        None => return Ok(response),
+        Some(data) => data,
+    };
+    let data = match parse_execute_response_data(data.as_slice())?.data {
+        None => return Ok(response),
+        Some(data) => data,
+    };
+    let event = match from_json::<NameServiceExecuteMsgResponse>(&data) {
+        Ok(value) => Event::new("my-collection-manager")
+            .add_attribute("token-count-after", value.num_tokens.to_string()),
+        Err(_) => {
+            Event::new("my-collection-manager").add_attribute("unparsed-data", data.to_base64())
+        }
+    };
+    Ok(response.add_event(event))
+}
+
+/// Only reached when the mint failure policy, or the settlement of an auction, asked for the
+/// failure to come back to the reply.
+fn refund_failed_mint(deps: DepsMut, context: PassThroughContext, error: String) -> ContractResult {
+    let fee_bps = match CONFIG.load(deps.storage)?.mint_failure_policy {
+        MintFailurePolicy::Revert => 0,
+        MintFailurePolicy::Refund { fee_bps } => fee_bps,
+    };
+    if let Some(counted_mint) = &context.counted_mint {
+        release_mint(
+            deps.storage,
+            &context.collection,
+            &context.sender,
+            counted_mint,
+        )?;
+    }
+    let mut response = Response::default();
+    let mut failure_event = Event::new("my-collection-manager")
+        .add_attribute("mint-failed", context.collection.to_owned())
+        .add_attribute("mint-failed-error", error);
+    if let Some(token_id) = context.token_id {
+        failure_event = failure_event.add_attribute("mint-failed-token-id", token_id);
+    }
+    if let Some(payment) = context.payment {
+        let fee = payment.amount.multiply_ratio(fee_bps, MAX_FEE_BPS);
+        let refund = payment.amount.strict_sub(fee);
+        if !fee.is_zero() {
+            let payment_params = load_payment_params(deps.storage, &context.collection)?;
+            let fee_payment = Coin {
+                denom: payment.denom.to_owned(),
+                amount: fee,
+            };
+            response = response.add_messages(pay_out(
+                deps.storage,
+                &context.collection,
+                split_payouts(&payment_params, &fee_payment),
+            )?);
+            failure_event = failure_event.add_attribute(
+                "mint-fee",
+                Coin {
+                    denom: payment.denom.to_owned(),
+                    amount: fee,
+                }
+                .to_string(),
+            );
+        }
+        if !refund.is_zero() {
+            let refund = Coin {
+                denom: payment.denom,
+                amount: refund,
+            };
+            failure_event = failure_event.add_attribute("mint-refund", refund.to_string());
+            response = response.add_message(BankMsg::Send {
+                to_address: context.sender.to_string(),
+                amount: vec![refund],
+            });
+        }
+    }
+    Ok(response.add_event(failure_event))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+    match msg {
+        QueryMsg::GetPaymentParams => Ok(to_json_binary(&GetPaymentParamsResponse {
+            payment_params: PAYMENT_PARAMS.load(deps.storage)?,
+        })?),
+        QueryMsg::GetConfig => Ok(to_json_binary(&GetConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        })?),
+        QueryMsg::GetOwnership => Ok(to_json_binary(&GetOwnershipResponse {
+            owner: OWNER.may_load(deps.storage)?,
+            pending_owner: PENDING_OWNER.may_load(deps.storage)?,
+        })?),
+        QueryMsg::GetCollectionPaymentParams { collection } => {
+            let collection = deps.api.addr_validate(&collection)?;
+            Ok(to_json_binary(&GetPaymentParamsResponse {
+                payment_params: load_payment_params(deps.storage, &collection)?,
+            })?)
+        }
+        QueryMsg::ListCollectionPaymentParams { start_after, limit } => Ok(to_json_binary(
+            &query_list_collection_payment_params(deps, start_after, limit)?,
+        )?),
+        QueryMsg::ListCollections { start_after, limit } => Ok(to_json_binary(
+            &query_list_collections(deps, start_after, limit)?,
+        )?),
+        QueryMsg::SimulateMint {
+            collection,
+            sender,
+            funds,
+            token_id,
+            years,
+        } => Ok(to_json_binary(&query_simulate_mint(
+            deps, env, collection, sender, funds, token_id, years,
+        )?)?),
+        QueryMsg::SimulateAction {
+            collection,
+            sender,
+            funds,
+            action,
+        } => Ok(to_json_binary(&query_simulate_action(
+            deps, collection, sender, funds, action,
+        )?)?),
+        QueryMsg::ListAcceptedCw20 { start_after, limit } => Ok(to_json_binary(
+            &query_list_accepted_cw20(deps, start_after, limit)?,
+        )?),
+        QueryMsg::ActiveMintPhase { collection } => {
+            let collection = deps.api.addr_validate(&collection)?;
+            let phases = MINT_PHASES
+                .may_load(deps.storage, &collection)?
+                .unwrap_or_default();
+            Ok(to_json_binary(&ActiveMintPhaseResponse {
+                phase: find_active_mint_phase(&phases, env.block.time).cloned(),
+            })?)
+        }
+        QueryMsg::MintAllowance {
+            collection,
+            address,
+        } => Ok(to_json_binary(&query_mint_allowance(
+            deps, env, collection, address,
+        )?)?),
+        QueryMsg::MintQuota {
+            collection,
+            address,
+        } => Ok(to_json_binary(&query_mint_quota(
+            deps, collection, address,
+        )?)?),
+        QueryMsg::ValidateTokenId {
+            collection,
+            token_id,
+        } => {
+            let collection = deps.api.addr_validate(&collection)?;
+            let validation = match TOKEN_ID_POLICIES.may_load(deps.storage, &collection)? {
+                None => Ok(token_id),
+                Some(policy) => policy.normalize(&token_id),
+            };
+            Ok(to_json_binary(&match validation {
+                Ok(token_id) => ValidateTokenIdResponse {
+                    token_id: Some(token_id),
+                    error: None,
+                },
+                Err(err) => ValidateTokenIdResponse {
+                    token_id: None,
+                    error: Some(err.to_string()),
+                },
+            })?)
+        }
+        QueryMsg::MintCommitment { commitment } => Ok(to_json_binary(&query_mint_commitment(
+            deps, env, commitment,
+        )?)?),
+        QueryMsg::Expiry {
+            collection,
+            token_id,
+        } => {
+            let collection = deps.api.addr_validate(&collection)?;
+            let expires_at = REGISTRATIONS.may_load(deps.storage, (&collection, &token_id))?;
+            Ok(to_json_binary(&ExpiryResponse {
+                expires_at,
+                expired: matches!(expires_at, Some(expires_at) if expires_at <= env.block.time),
+            })?)
+        }
+        QueryMsg::ActiveAuctions { start_after, limit } => Ok(to_json_binary(
+            &query_active_auctions(deps, start_after, limit)?,
+        )?),
+        QueryMsg::BidHistory {
+            auction_id,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query_bid_history(
+            deps,
+            auction_id,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::CurrentMintPrice {
+            collection,
+            token_id,
+        } => Ok(to_json_binary(&query_current_mint_price(
+            deps, env, collection, token_id,
+        )?)?),
+        QueryMsg::NextMintPrices { collection, count } => Ok(to_json_binary(
+            &query_next_mint_prices(deps, env, collection, count)?,
+        )?),
+        QueryMsg::PassThroughPolicy { collection } => {
+            let collection = deps.api.addr_validate(&collection)?;
+            let policy = PASS_THROUGH_POLICIES
+                .may_load(deps.storage, &collection)?
+                .unwrap_or_default();
+            Ok(to_json_binary(&PassThroughPolicyResponse {
+                rules: CollectionAction::ALL
+                    .into_iter()
+                    .map(|action| ActionRule {
+                        access: policy.access(&action),
+                        action,
+                    })
+                    .collect(),
+            })?)
+        }
+        QueryMsg::PauseStatus { collection } => {
+            let collection = match collection {
+                None => None,
+                Some(collection) => COLLECTION_PAUSES
+                    .may_load(deps.storage, &deps.api.addr_validate(&collection)?)?,
+            };
+            Ok(to_json_binary(&PauseStatusResponse {
+                global: GLOBAL_PAUSE.may_load(deps.storage)?,
+                collection,
+                guardian: GUARDIAN.may_load(deps.storage)?,
+            })?)
+        }
+        QueryMsg::Claimable { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_json_binary(&ClaimableResponse {
+                balances: query_balances(deps, CLAIMABLE_BALANCES, &address)?,
+            })?)
+        }
+        QueryMsg::CollectionRevenue { collection } => {
+            let collection = deps.api.addr_validate(&collection)?;
+            Ok(to_json_binary(&CollectionRevenueResponse {
+                revenue: query_balances(deps, COLLECTION_REVENUE, &collection)?,
+            })?)
+        }
+        QueryMsg::RebatePool { collection } => {
+            let collection = deps.api.addr_validate(&collection)?;
+            Ok(to_json_binary(&RebatePoolResponse {
+                funds: query_balances(deps, REBATE_POOLS, &collection)?,
+            })?)
+        }
+        QueryMsg::ExpiringTokens {
+            start,
+            end,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query_expiring_tokens(
+            deps,
+            start,
+            end,
+            start_after,
+            limit,
+        )?)?),
+    }
+}
+
+fn query_list_collection_payment_params(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<ListCollectionPaymentParamsResponse, ContractError> {
+    let start_after = start_after
+        .map(|collection| deps.api.addr_validate(&collection))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let collections = COLLECTION_PAYMENT_PARAMS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(collection, payment_params)| CollectionPaymentParams {
+                collection,
+                payment_params,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListCollectionPaymentParamsResponse { collections })
+}
+
+fn query_list_collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<ListCollectionsResponse, ContractError> {
+    let start_after = start_after
+        .map(|collection| deps.api.addr_validate(&collection))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let collections = COLLECTIONS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(collection, collection_info)| ManagedCollection {
+                collection,
+                collection_info,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListCollectionsResponse { collections })
+}
+
+fn query_simulate_action(
+    deps: Deps,
+    collection: String,
+    sender: String,
+    funds: Vec<Coin>,
+    action: CollectionAction,
+) -> Result<SimulateMintResponse, ContractError> {
+    let sender = deps.api.addr_validate(&sender)?;
+    if action == CollectionAction::Mint {
+        return Err(ContractError::ActionFeeForMint);
+    }
+    let plan = assert_registered_collection(deps, &collection).and_then(|collection_addr| {
+        plan_action_fee(deps, &collection_addr, &sender, &action, &funds)
+    });
+    Ok(match plan {
+        Ok(plan) => SimulateMintResponse {
+            payment: plan.payment,
+            payouts: plan.payouts,
+            refund: plan.refund,
+            rebate: plan.rebate,
+            error: None,
+        },
+        Err(err) => SimulateMintResponse {
+            payment: None,
+            payouts: vec![],
+            refund: vec![],
+            rebate: vec![],
+            error: Some(err.to_string()),
+        },
+    })
+}
+
+fn query_simulate_mint(
+    deps: Deps,
+    env: Env,
+    collection: String,
+    sender: String,
+    funds: Vec<Coin>,
+    token_id: Option<String>,
+    years: Option<u32>,
+) -> Result<SimulateMintResponse, ContractError> {
+    deps.api.addr_validate(&sender)?;
+    let mint_options = MintOptions {
+        years,
+        ..MintOptions::default()
+    };
+    let plan = assert_registered_collection(deps, &collection).and_then(|collection_addr| {
+        let token_id = token_id
+            .map(|token_id| normalize_token_id(deps.storage, &collection_addr, &token_id))
+            .transpose()?;
+        plan_mint_funds(
+            deps,
+            env.block.time,
+            &collection_addr,
+            token_id.as_deref(),
+            Some(&mint_options),
+            &funds,
+        )
+    });
+    Ok(match plan {
+        Ok(plan) => SimulateMintResponse {
+            payment: plan.payment,
+            payouts: plan.payouts,
+            refund: plan.refund,
+            rebate: plan.rebate,
+            error: None,
+        },
+        Err(err) => SimulateMintResponse {
+            payment: None,
+            payouts: vec![],
+            refund: vec![],
+            rebate: vec![],
+            error: Some(err.to_string()),
+        },
+    })
+}
+
+fn query_mint_allowance(
+    deps: Deps,
+    env: Env,
+    collection: String,
+    address: String,
+) -> Result<MintAllowanceResponse, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let address = deps.api.addr_validate(&address)?;
+    let phases = MINT_PHASES
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+    Ok(match find_active_mint_phase(&phases, env.block.time) {
+        None => MintAllowanceResponse {
+            phase: None,
+            minted: 0,
+            remaining: None,
+        },
+        Some(phase) => {
+            let minted = PHASE_MINT_COUNTS
+                .may_load(deps.storage, (&collection, phase.name.as_str(), &address))?
+                .unwrap_or_default();
+            MintAllowanceResponse {
+                phase: Some(phase.name.to_owned()),
+                minted,
+                remaining: phase
+                    .per_address_limit
+                    .map(|limit| limit.saturating_sub(minted)),
+            }
+        }
+    })
+}
+
+fn query_mint_commitment(
+    deps: Deps,
+    env: Env,
+    commitment: HexBinary,
+) -> Result<MintCommitmentResponse, ContractError> {
+    let committed = MINT_COMMITMENTS.may_load(deps.storage, commitment.as_slice())?;
+    let commit_reveal = CONFIG.load(deps.storage)?.commit_reveal;
+    let (revealable, expired) = match (&committed, commit_reveal) {
+        (Some(committed), Some(commit_reveal)) => {
+            let expired = committed.height + commit_reveal.max_blocks <= env.block.height;
+            let revealable =
+                !expired && committed.height + commit_reveal.min_blocks <= env.block.height;
+            (revealable, expired)
+        }
+        (Some(_), None) => (false, true),
+        (None, _) => (false, false),
+    };
+    Ok(MintCommitmentResponse {
+        commitment: committed,
+        revealable,
+        expired,
+    })
+}
+
+fn query_expiring_tokens(
+    deps: Deps,
+    start: Timestamp,
+    end: Timestamp,
+    start_after: Option<Registration>,
+    limit: Option<u32>,
+) -> Result<ExpiringTokensResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    // No address is empty, so it bounds a second from below.
+    let no_collection = Addr::unchecked("");
+    let min = match &start_after {
+        None => Bound::inclusive((start.seconds(), &no_collection, "")),
+        Some(after) => Bound::exclusive((
+            after.expires_at.seconds(),
+            &after.collection,
+            after.token_id.as_str(),
+        )),
+    };
+    let max = Bound::exclusive((end.seconds(), &no_collection, ""));
+    let registrations = REGISTRATION_EXPIRIES
+        .keys(deps.storage, Some(min), Some(max), Order::Ascending)
+        .take(limit)
+        .map(|key| {
+            key.map(|(seconds, collection, token_id)| Registration {
+                collection,
+                token_id,
+                expires_at: Timestamp::from_seconds(seconds),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ExpiringTokensResponse { registrations })
+}
+
+fn query_active_auctions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<ActiveAuctionsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let auctions = AUCTIONS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|auction| auction.map(|(_, auction)| auction))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ActiveAuctionsResponse { auctions })
+}
+
+fn query_bid_history(
+    deps: Deps,
+    auction_id: u64,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> Result<BidHistoryResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let bids = AUCTION_BIDS
+        .prefix(auction_id)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|bid| bid.map(|(_, bid)| bid))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(BidHistoryResponse { bids })
+}
+
+fn query_current_mint_price(
+    deps: Deps,
+    env: Env,
+    collection: String,
+    token_id: Option<String>,
+) -> Result<CurrentMintPriceResponse, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let payment_params = load_mint_payment_params(deps.storage, &collection, env.block.time)?;
+    let prices = match token_id {
+        None => payment_params.accepted_mint_prices(),
+        Some(token_id) => payment_params.mint_prices_for(&normalize_token_id(
+            deps.storage,
+            &collection,
+            &token_id,
+        )?)?,
+    };
+    let clearing_price = match &payment_params.dutch_auction {
+        Some(dutch_auction) if dutch_auction.refund_to_clearing_price => DUTCH_CLEARING_PRICES
+            .may_load(deps.storage, (&collection, dutch_auction.start.seconds()))?
+            .map(|amount| Coin {
+                denom: dutch_auction.start_price.denom.to_owned(),
+                amount,
+            }),
+        _ => None,
+    };
+    Ok(CurrentMintPriceResponse {
+        prices,
+        clearing_price,
+    })
+}
+
+/// The amounts per denom that a balance map holds for an address.
+fn query_balances(
+    deps: Deps,
+    balances: Map<(&Addr, &str), Uint128>,
+    address: &Addr,
+) -> StdResult<Vec<Coin>> {
+    balances
+        .prefix(address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|balance| balance.map(|(denom, amount)| Coin { denom, amount }))
+        .collect()
+}
+
+fn query_next_mint_prices(
+    deps: Deps,
+    env: Env,
+    collection: String,
+    count: u32,
+) -> StdResult<NextMintPricesResponse> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let payment_params = load_mint_payment_params(deps.storage, &collection, env.block.time)?;
+    let supply = query_token_count(deps, &collection)?;
+    let prices = (supply..supply + u64::from(count.min(MAX_PAGE_LIMIT)))
+        .filter_map(|supply| {
+            supply_payment_params(&payment_params, supply)
+                .map(|payment_params| payment_params.mint_price)
+                .transpose()
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let total = match prices.first() {
+        None => None,
+        Some(first) => Some(Coin {
+            denom: first.denom.to_owned(),
+            amount: prices.iter().try_fold(Uint128::zero(), |total, price| {
+                total.checked_add(price.amount)
+            })?,
+        }),
+    };
+    Ok(NextMintPricesResponse {
+        supply,
+        prices,
+        total,
+    })
+}
+
+fn query_mint_quota(
+    deps: Deps,
+    collection: String,
+    address: String,
+) -> Result<MintQuotaResponse, ContractError> {
+    let collection = deps.api.addr_validate(&collection)?;
+    let address = deps.api.addr_validate(&address)?;
+    let limits = MINT_LIMITS
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+    let minted_by_address = ADDRESS_MINT_COUNTS
+        .may_load(deps.storage, (&collection, &address))?
+        .unwrap_or_default();
+    let total_minted = COLLECTION_MINT_COUNTS
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+    Ok(MintQuotaResponse {
+        remaining_for_address: limits
+            .per_address
+            .map(|limit| limit.saturating_sub(minted_by_address)),
+        remaining_supply: limits
+            .max_supply
+            .map(|max_supply| max_supply.saturating_sub(total_minted)),
+        limits,
+        minted_by_address,
+        total_minted,
+    })
+}
+
+fn query_list_accepted_cw20(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<ListAcceptedCw20Response, ContractError> {
+    let start_after = start_after
+        .map(|token| deps.api.addr_validate(&token))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let tokens = ACCEPTED_CW20
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListAcceptedCw20Response { tokens })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult {
+    match msg {
+        SudoMsg::UpdatePaymentParams(payment_params) => {
+            sudo_update_payment_params(deps, payment_params)
+        }
+        SudoMsg::SetCollectionPaymentParams {
+            collection,
+            payment_params,
+        } => set_collection_payment_params(deps, collection, payment_params),
+        SudoMsg::RemoveCollectionPaymentParams { collection } => {
+            remove_collection_payment_params(deps, collection)
+        }
+        SudoMsg::AddCollection { collection, label } => {
+            add_collection(deps, env, collection, label)
+        }
+        SudoMsg::RemoveCollection { collection } => remove_collection(deps, collection),
+        SudoMsg::UpdateConfig(config) => update_config(deps, config),
+        SudoMsg::SetMintPhases { collection, phases } => set_mint_phases(deps, collection, phases),
+        SudoMsg::SetMintLimits { collection, limits } => set_mint_limits(deps, collection, limits),
+        SudoMsg::SetTokenIdPolicy { collection, policy } => {
+            set_token_id_policy(deps, collection, policy)
+        }
+        SudoMsg::SetPassThroughPolicy { collection, policy } => {
+            set_pass_through_policy(deps, collection, policy)
+        }
+        SudoMsg::AddAcceptedCw20 { token } => add_accepted_cw20(deps, token),
+        SudoMsg::RemoveAcceptedCw20 { token } => remove_accepted_cw20(deps, token),
+        SudoMsg::SetOwner { owner } => sudo_set_owner(deps, owner),
+        SudoMsg::Pause {
+            collection,
+            allowed_actions,
+        } => pause(deps, collection, allowed_actions),
+        SudoMsg::Unpause { collection } => unpause(deps, collection),
+        SudoMsg::SetGuardian { guardian } => set_guardian(deps, guardian),
+    }
+}
+
+fn pause(
+    deps: DepsMut,
+    collection: Option<String>,
+    allowed_actions: Vec<CollectionAction>,
+) -> ContractResult {
+    let pause_state = PauseState { allowed_actions };
+    let paused = match collection {
+        None => {
+            GLOBAL_PAUSE.save(deps.storage, &pause_state)?;
+            "all".to_owned()
+        }
+        Some(collection) => {
+            let collection = assert_registered_collection(deps.as_ref(), &collection)?;
+            COLLECTION_PAUSES.save(deps.storage, &collection, &pause_state)?;
+            collection.to_string()
+        }
+    };
+    let pause_event = Event::new("my-collection-manager")
+        .add_attribute("pause", paused)
+        .add_attribute(
+            "pause-allowed-actions",
+            pause_state
+                .allowed_actions
+                .iter()
+                .map(CollectionAction::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    Ok(Response::default().add_event(pause_event))
+}
+
+fn unpause(deps: DepsMut, collection: Option<String>) -> ContractResult {
+    let unpaused = match collection {
+        None => {
+            GLOBAL_PAUSE.remove(deps.storage);
+            "all".to_owned()
+        }
+        Some(collection) => {
+            let collection = deps.api.addr_validate(&collection)?;
+            COLLECTION_PAUSES.remove(deps.storage, &collection);
+            collection.to_string()
+        }
+    };
+    Ok(Response::default()
+        .add_event(Event::new("my-collection-manager").add_attribute("unpause", unpaused)))
+}
+
+fn set_guardian(deps: DepsMut, guardian: Option<String>) -> ContractResult {
+    let guardian_event = match guardian {
+        None => {
+            GUARDIAN.remove(deps.storage);
+            Event::new("my-collection-manager").add_attribute("update-guardian", "none")
+        }
+        Some(guardian) => {
+            let guardian = deps.api.addr_validate(&guardian)?;
+            GUARDIAN.save(deps.storage, &guardian)?;
+            Event::new("my-collection-manager").add_attribute("update-guardian", guardian)
+        }
+    };
+    Ok(Response::default().add_event(guardian_event))
+}
+
+fn sudo_set_owner(deps: DepsMut, owner: Option<String>) -> ContractResult {
+    PENDING_OWNER.remove(deps.storage);
+    let sudo_event = match owner {
+        None => {
+            OWNER.remove(deps.storage);
+            Event::new("my-collection-manager").add_attribute("update-owner", "none")
+        }
+        Some(owner) => {
+            let owner = deps.api.addr_validate(&owner)?;
+            OWNER.save(deps.storage, &owner)?;
+            Event::new("my-collection-manager").add_attribute("update-owner", owner)
+        }
+    };
+    Ok(Response::default().add_event(sudo_event))
+}
+
+fn update_config(deps: DepsMut, config: Config) -> ContractResult {
+    config.validate()?;
+    CONFIG.save(deps.storage, &config)?;
+    let config_event = Event::new("my-collection-manager")
+        .add_attribute(
+            "update-config-collection-code-id",
+            config
+                .collection_code_id
+                .map_or("none".to_owned(), |code_id| code_id.to_string()),
+        )
+        .add_attribute(
+            "update-config-max-batch-size",
+            config
+                .max_batch_size
+                .map_or("none".to_owned(), |max_batch_size| {
+                    max_batch_size.to_string()
+                }),
+        )
+        .add_attribute(
+            "update-config-mint-failure-policy",
+            match config.mint_failure_policy {
+                MintFailurePolicy::Revert => "revert".to_owned(),
+                MintFailurePolicy::Refund { fee_bps } => format!("refund:{}", fee_bps),
+            },
+        )
+        .add_attribute(
+            "update-config-commit-reveal",
+            config
+                .commit_reveal
+                .as_ref()
+                .map_or("none".to_owned(), |commit_reveal| {
+                    format!("{}-{}", commit_reveal.min_blocks, commit_reveal.max_blocks)
+                }),
+        )
+        .add_attribute(
+            "update-config-accrue-revenue",
+            config.accrue_revenue.to_string(),
+        );
+    let config_event = match config.commit_reveal {
+        None => config_event,
+        Some(commit_reveal) => config_event
+            .add_attribute(
+                "update-config-commit-reveal-deposit",
+                commit_reveal
+                    .deposit
+                    .map_or("none".to_owned(), |deposit| deposit.to_string()),
+            )
+            .add_attribute(
+                "update-config-commit-reveal-deposit-refund",
+                match commit_reveal.deposit_refund {
+                    CommitDepositRefund::Always => "always",
+                    CommitDepositRefund::OnReveal => "on-reveal",
+                    CommitDepositRefund::Never => "never",
+                },
+            )
+            .add_attribute(
+                "update-config-commit-reveal-required",
+                commit_reveal.required.to_string(),
+            ),
+    };
+    Ok(Response::default().add_event(config_event))
+}
+
+fn sudo_update_payment_params(deps: DepsMut, payment_params: PaymentParams) -> ContractResult {
+    payment_params.validate()?;
+    PAYMENT_PARAMS.save(deps.storage, &payment_params)?;
+    let sudo_event = Event::new("my-collection-manager");
+    let sudo_event = append_payment_params_attributes(sudo_event, payment_params);
+    Ok(Response::default().add_event(sudo_event))
+}
+
+fn set_collection_payment_params(
+    deps: DepsMut,
+    collection: String,
+    payment_params: PaymentParams,
+) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    payment_params.validate()?;
+    COLLECTION_PAYMENT_PARAMS.save(deps.storage, &collection, &payment_params)?;
+    let set_event = Event::new("my-collection-manager")
+        .add_attribute("update-collection-payment-params", collection);
+    let set_event = append_payment_params_attributes(set_event, payment_params);
+    Ok(Response::default().add_event(set_event))
+}
+
+fn remove_collection_payment_params(deps: DepsMut, collection: String) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    COLLECTION_PAYMENT_PARAMS.remove(deps.storage, &collection);
+    let remove_event = Event::new("my-collection-manager")
+        .add_attribute("remove-collection-payment-params", collection);
+    Ok(Response::default().add_event(remove_event))
+}
+
+fn set_mint_phases(deps: DepsMut, collection: String, phases: Vec<MintPhase>) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    validate_mint_phases(&phases)?;
+    let phase_names = if phases.is_empty() {
+        MINT_PHASES.remove(deps.storage, &collection);
+        "none".to_owned()
+    } else {
+        MINT_PHASES.save(deps.storage, &collection, &phases)?;
+        phases
+            .iter()
+            .map(|phase| phase.name.to_owned())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let set_event = Event::new("my-collection-manager")
+        .add_attribute("update-mint-phases", collection)
+        .add_attribute("update-mint-phases-names", phase_names);
+    Ok(Response::default().add_event(set_event))
+}
+
+fn set_mint_limits(deps: DepsMut, collection: String, limits: MintLimits) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    limits.validate()?;
+    if limits == MintLimits::default() {
+        MINT_LIMITS.remove(deps.storage, &collection);
+    } else {
+        MINT_LIMITS.save(deps.storage, &collection, &limits)?;
+    }
+    let set_event = Event::new("my-collection-manager")
+        .add_attribute("update-mint-limits", collection)
+        .add_attribute(
+            "update-mint-limits-per-address",
+            limits
+                .per_address
+                .map_or("none".to_owned(), |per_address| per_address.to_string()),
+        )
+        .add_attribute(
+            "update-mint-limits-max-supply",
+            limits
+                .max_supply
+                .map_or("none".to_owned(), |max_supply| max_supply.to_string()),
+        );
+    Ok(Response::default().add_event(set_event))
+}
+
+fn set_pass_through_policy(
+    deps: DepsMut,
+    collection: String,
+    policy: PassThroughPolicy,
+) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    policy.validate()?;
+    if policy == PassThroughPolicy::default() {
+        PASS_THROUGH_POLICIES.remove(deps.storage, &collection);
+    } else {
+        PASS_THROUGH_POLICIES.save(deps.storage, &collection, &policy)?;
+    }
+    let set_event = Event::new("my-collection-manager")
+        .add_attribute("update-pass-through-policy", collection)
+        .add_attribute(
+            "update-pass-through-policy-rules",
+            policy
+                .rules
+                .iter()
+                .map(|rule| {
+                    format!(
+                        "{}:{}",
+                        rule.action.as_str(),
+                        match rule.access {
+                            ActionAccess::Public => "public",
+                            ActionAccess::OwnerOnly => "owner-only",
+                            ActionAccess::Forbidden => "forbidden",
+                        }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    Ok(Response::default().add_event(set_event))
+}
+
+fn set_token_id_policy(deps: DepsMut, collection: String, policy: TokenIdPolicy) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    policy.validate()?;
+    if policy == TokenIdPolicy::default() {
+        TOKEN_ID_POLICIES.remove(deps.storage, &collection);
+    } else {
+        TOKEN_ID_POLICIES.save(deps.storage, &collection, &policy)?;
+    }
+    let set_event = Event::new("my-collection-manager")
+        .add_attribute("update-token-id-policy", collection)
+        .add_attribute(
+            "update-token-id-policy-charset",
+            match policy.charset {
+                None => "any".to_owned(),
+                Some(TokenIdCharset::Alphanumeric) => "alphanumeric".to_owned(),
+                Some(TokenIdCharset::Hostname) => "hostname".to_owned(),
+                Some(TokenIdCharset::Custom { chars }) => format!("custom:{}", chars),
+            },
+        )
+        .add_attribute(
+            "update-token-id-policy-min-length",
+            policy
+                .min_length
+                .map_or("none".to_owned(), |min_length| min_length.to_string()),
+        )
+        .add_attribute(
+            "update-token-id-policy-max-length",
+            policy
+                .max_length
+                .map_or("none".to_owned(), |max_length| max_length.to_string()),
+        )
+        .add_attribute(
+            "update-token-id-policy-force-lowercase",
+            policy.force_lowercase.to_string(),
+        );
+    let set_event = if policy.reserved.is_empty() {
+        set_event
+    } else {
+        set_event.add_attribute("update-token-id-policy-reserved", policy.reserved.join(","))
+    };
+    Ok(Response::default().add_event(set_event))
+}
+
+fn add_collection(deps: DepsMut, env: Env, collection: String, label: String) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    let contract_info = deps
+        .querier
+        .query_wasm_contract_info(collection.to_owned())?;
+    let collection_info = CollectionInfo {
+        label,
+        code_id: contract_info.code_id,
+        created_at: env.block.time,
+    };
+    COLLECTIONS.save(deps.storage, &collection, &collection_info)?;
+    let add_event = Event::new("my-collection-manager")
+        .add_attribute("add-collection", collection)
+        .add_attribute("add-collection-label", collection_info.label)
+        .add_attribute(
+            "add-collection-code-id",
+            collection_info.code_id.to_string(),
+        );
+    Ok(Response::default().add_event(add_event))
+}
+
+fn remove_collection(deps: DepsMut, collection: String) -> ContractResult {
+    let collection = deps.api.addr_validate(&collection)?;
+    if !COLLECTIONS.has(deps.storage, &collection) {
+        return Err(ContractError::UnregisteredCollection {
+            collection: collection.to_string(),
+        });
+    }
+    COLLECTIONS.remove(deps.storage, &collection);
+    let remove_event =
+        Event::new("my-collection-manager").add_attribute("remove-collection", collection);
+    Ok(Response::default().add_event(remove_event))
+}
+
+fn add_accepted_cw20(deps: DepsMut, token: String) -> ContractResult {
+    let token = deps.api.addr_validate(&token)?;
+    ACCEPTED_CW20.save(deps.storage, &token, &Empty {})?;
+    let add_event = Event::new("my-collection-manager").add_attribute("add-accepted-cw20", token);
+    Ok(Response::default().add_event(add_event))
+}
+
+fn remove_accepted_cw20(deps: DepsMut, token: String) -> ContractResult {
+    let token = deps.api.addr_validate(&token)?;
+    if !ACCEPTED_CW20.has(deps.storage, &token) {
+        return Err(ContractError::UnacceptedCw20 {
+            token: token.to_string(),
+        });
+    }
+    ACCEPTED_CW20.remove(deps.storage, &token);
+    let remove_event =
+        Event::new("my-collection-manager").add_attribute("remove-accepted-cw20", token);
+    Ok(Response::default().add_event(remove_event))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> ContractResult {
+    // Instances from before cw2, and 0.1.0 ones, have no config, owner or registry yet.
+    if let Ok(ContractVersion {
+        contract: _,
+        version,
+    }) = get_contract_version(deps.storage)
+    {
+        if version != "0.1.0" {
+            return Err(ContractError::Version(VersionError::WrongVersion {
+                expected: "0.1.0".to_owned(),
+                found: version,
+            }));
+        }
+    }
+    let owner = match msg.owner {
+        Some(owner) => Some(deps.api.addr_validate(&owner)?),
+        None if OWNER.may_load(deps.storage)?.is_none() => return Err(ContractError::MissingOwner),
+        None => None,
+    };
+    msg.payment_params.validate()?;
+    PAYMENT_PARAMS.save(deps.storage, &msg.payment_params)?;
+    if CONFIG.may_load(deps.storage)?.is_none() {
+        CONFIG.save(deps.storage, &Config::default())?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let mut migrate_event = Event::new("my-collection-manager")
+        .add_attribute("update-contract-version", CONTRACT_VERSION);
+    if let Some(owner) = owner {
+        OWNER.save(deps.storage, &owner)?;
+        PENDING_OWNER.remove(deps.storage);
+        migrate_event = migrate_event.add_attribute("update-owner", owner);
+    }
+    let migrate_event = append_payment_params_attributes(migrate_event, msg.payment_params);
+    let mut response = Response::default().add_event(migrate_event);
+    for MigrateCollection { collection, label } in msg.collections {
+        let added = add_collection(deps.branch(), env.to_owned(), collection, label)?;
+        response = response.add_events(added.events);
+    }
+    Ok(response)
+}
+
+fn append_payment_params_attributes(my_event: Event, payment_params: PaymentParams) -> Event {
+    let my_event = my_event.add_attribute(
+        "update-payment-params-beneficiary",
+        payment_params.beneficiary,
+    );
+    let my_event = if payment_params.revenue_split.is_empty() {
+        my_event
+    } else {
+        my_event.add_attribute(
+            "update-payment-params-revenue-split",
+            payment_params
+                .revenue_split
+                .iter()
+                .map(|share| format!("{}:{}", share.recipient, share.weight))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    };
+    let my_event = if payment_params.alternative_mint_prices.is_empty() {
+        my_event
+    } else {
+        my_event.add_attribute(
+            "update-payment-params-alternative-mint-prices",
+            payment_params
+                .alternative_mint_prices
+                .iter()
+                .map(Coin::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    };
+    let my_event = if payment_params.cw20_mint_prices.is_empty() {
+        my_event
+    } else {
+        my_event.add_attribute(
+            "update-payment-params-cw20-mint-prices",
+            payment_params
+                .cw20_mint_prices
+                .iter()
+                .map(|price| format!("{}{}", price.amount, price.address))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    };
+    let my_event = if payment_params.length_prices.is_empty() {
+        my_event
+    } else {
+        my_event.add_attribute(
+            "update-payment-params-length-prices",
+            payment_params
+                .length_prices
+                .iter()
+                .map(|tier| format!("{}:{}", tier.min_length, tier.price))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    };
+    let my_event = match payment_params.dutch_auction {
+        None => my_event,
+        Some(dutch_auction) => my_event
+            .add_attribute(
+                "update-payment-params-dutch-auction",
+                format!(
+                    "{}-{}{}",
+                    dutch_auction.start_price.amount,
+                    dutch_auction.end_price,
+                    dutch_auction.start_price.denom
+                ),
+            )
+            .add_attribute(
+                "update-payment-params-dutch-auction-start",
+                dutch_auction.start.seconds().to_string(),
+            )
+            .add_attribute(
+                "update-payment-params-dutch-auction-duration",
+                dutch_auction.duration.to_string(),
+            )
+            .add_attribute(
+                "update-payment-params-dutch-auction-decay",
+                match dutch_auction.decay {
+                    PriceDecay::Linear => "linear".to_owned(),
+                    PriceDecay::Stepwise { step } => format!("stepwise-{}", step),
+                },
+            )
+            .add_attribute(
+                "update-payment-params-dutch-auction-refund-to-clearing-price",
+                dutch_auction.refund_to_clearing_price.to_string(),
+            ),
+    };
+    let my_event = if payment_params.action_fees.is_empty() {
+        my_event
+    } else {
+        my_event.add_attribute(
+            "update-payment-params-action-fees",
+            payment_params
+                .action_fees
+                .iter()
+                .map(|fee| {
+                    let sign = if fee.rebate { "-" } else { "" };
+                    format!("{}:{}{}", fee.action.as_str(), sign, fee.price)
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    };
+    let my_event = match payment_params.bonding_curve {
+        None => my_event,
+        Some(bonding_curve) => my_event.add_attribute(
+            "update-payment-params-bonding-curve",
+            match bonding_curve.shape {
+                CurveShape::Linear { slope } => {
+                    format!("linear:{}+{}", bonding_curve.base_price, slope)
+                }
+                CurveShape::Exponential { growth_bps } => {
+                    format!("exponential:{}+{}bps", bonding_curve.base_price, growth_bps)
+                }
+                CurveShape::Steps { steps } => format!(
+                    "steps:{}{}",
+                    bonding_curve.base_price,
+                    steps
+                        .iter()
+                        .map(|step| format!(",{}:{}", step.min_supply, step.price))
+                        .collect::<String>()
+                ),
+            },
+        ),
+    };
+    let my_event = match payment_params.yearly_price {
+        None => my_event,
+        Some(yearly_price) => my_event.add_attribute(
+            "update-payment-params-yearly-price",
+            yearly_price.to_string(),
+        ),
+    };
+    match payment_params.mint_price {
+        None => my_event.add_attribute("update-payment-params-mint-price", "none"),
+        Some(mint_price) => my_event
+            .add_attribute("update-payment-params-mint-price-denom", mint_price.denom)
+            .add_attribute(
+                "update-payment-params-mint-price-amount",
+                mint_price.amount.to_string(),
+            ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        contract::ReplyCode,
+        error::ContractError,
+        msg::{
+            mint_commitment, ActionAccess, ActionFee, ActionRule, ActiveAuctionsResponse,
+            ActiveMintPhaseResponse, BatchMode, Bid, BondingCurve, ClaimableResponse,
+            CollectionAction, CollectionExecuteMsg, CollectionInfo, CollectionInstantiateMsg,
+            CollectionQueryMsg, CollectionRevenueResponse, CommitDepositRefund, CommitRevealConfig,
+            Config, CurrentMintPriceResponse, CurveShape, DutchAuctionPrice, ExecuteMsg,
+            ExpiringTokensResponse, ExpiryResponse, InstantiateMsg, LengthPrice, MigrateCollection,
+            MigrateMsg, MintAllowanceResponse, MintCommitment, MintCommitmentResponse,
+            MintFailurePolicy, MintLimits, MintOptions, MintPayout, MintPhase, MintQuotaResponse,
+            NameServiceExecuteMsgResponse, NextMintPricesResponse, PassThroughItem,
+            PassThroughPolicy, PassThroughPolicyResponse, PauseState, PauseStatusResponse,
+            PaymentParams, PriceDecay, QueryMsg, Reassignment, RebatePoolResponse, ReceiveMsg,
+            Registration, RevenueShare, SimulateMintResponse, SudoMsg, SupplyStep, TokenIdCharset,
+            TokenIdPolicy, ValidateTokenIdResponse, SECONDS_PER_YEAR,
+        },
+        state::{
+            PassThroughContext, ReplyContext, ADDRESS_MINT_COUNTS, AUCTIONS, COLLECTIONS,
+            COLLECTION_MINT_COUNTS, COLLECTION_PAYMENT_PARAMS, CONFIG, MINT_PHASES, OWNER,
+            PAYMENT_PARAMS, PENDING_OWNER, PHASE_MINT_COUNTS, REBATE_POOLS, REGISTRATIONS,
+            REPLY_CONTEXTS,
+        },
+    };
+    use cosmwasm_std::{
+        from_json,
+        testing::{self, MockApi, MockQuerier, MockStorage},
+        to_json_binary, Addr, Attribute, BankMsg, Binary, Coin, ContractInfoResponse,
+        ContractResult, CosmosMsg, Deps, DepsMut, Empty, Env, Event, HexBinary, OwnedDeps, Querier,
+        QuerierResult, QueryRequest, Reply, ReplyOn, Response, Storage, SubMsg, SubMsgResponse,
+        SubMsgResult, SystemError, SystemResult, Timestamp, Uint128, WasmMsg, WasmQuery,
+    };
+    use cw2::{assert_contract_version, set_contract_version, ContractVersion, VersionError};
+    use cw20::{Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw721::msg::{NumTokensResponse, OwnerOfResponse};
+    use sha2::{Digest, Sha256};
+    use std::marker::PhantomData;
+
+    const MOCK_CODE_ID: u64 = 7;
+
+    pub fn add_mock_collection(storage: &mut dyn Storage, collection: &str) {
+        COLLECTIONS
+            .save(
+                storage,
+                &Addr::unchecked(collection),
+                &CollectionInfo {
+                    label: collection.to_owned(),
+                    code_id: MOCK_CODE_ID,
+                    created_at: testing::mock_env().block.time,
+                },
+            )
+            .expect("Failed to save mock collection");
+    }
+
+    fn save_pass_through_context(storage: &mut dyn Storage) -> u64 {
+        super::save_reply_context(
+            storage,
+            ReplyCode::PassThrough,
+            &ReplyContext::PassThrough(PassThroughContext {
+                sender: Addr::unchecked("executer"),
+                collection: Addr::unchecked("collection"),
+                token_id: None,
+                payment: None,
+                held_payouts: vec![],
+                held_rebate: vec![],
+                counted_mint: None,
+            }),
+        )
+        .expect("Failed to save reply context")
+    }
+
+    fn encode_execute_response_data(data: &[u8]) -> Binary {
+        let mut encoded = vec![10u8];
+        let mut len = data.len();
+        while len >= 0x80 {
+            encoded.push((len & 0x7f) as u8 | 0x80);
+            len >>= 7;
+        }
+        encoded.push(len as u8);
+        encoded.extend_from_slice(data);
+        Binary::from(encoded)
+    }
+
+    pub fn mock_deps(
+        response: NumTokensResponse,
+    ) -> OwnedDeps<MockStorage, MockApi, NumTokensMockQuerier, Empty> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: NumTokensMockQuerier::new(MockQuerier::new(&[]), response),
+            custom_query_type: PhantomData,
+        }
+    }
+
+    pub struct NumTokensMockQuerier {
+        base: MockQuerier,
+        response: NumTokensResponse,
+    }
+
+    impl Querier for NumTokensMockQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_json::<QueryRequest<Empty>>(bin_request) {
                 Ok(request) => self.handle_query(&request),
                 Err(e) => SystemResult::Err(SystemError::InvalidRequest {
                     error: format!("Parsing query request: {}", e),
@@ -296,173 +4025,5049 @@ mod tests {
         }
     }
 
-    impl NumTokensMockQuerier {
-        pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
-            match request {
-                QueryRequest::Wasm(wasm_query) => match wasm_query {
-                    WasmQuery::Smart {
-                        contract_addr: _,
-                        msg,
-                    } => {
-                        let serialized = from_json::<CollectionQueryMsg>(msg)
-                            .map(|collection_query| match collection_query {
-                                CollectionQueryMsg::NumTokens {} => to_json_binary(&self.response)
-                                    .expect("Failed to serialize num tokens response"),
-                                _ => unimplemented!("{:?}", collection_query),
-                            })
-                            .expect("Failed to find serialised type");
-                        SystemResult::Ok(ContractResult::Ok(serialized))
-                    }
-                    _ => unimplemented!("{:?}", wasm_query),
-                },
-                _ => self.base.handle_query(request),
+    impl NumTokensMockQuerier {
+        pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+            match request {
+                QueryRequest::Wasm(wasm_query) => match wasm_query {
+                    WasmQuery::Smart { contract_addr, .. } if contract_addr == "unqueryable" => {
+                        SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.to_owned(),
+                        })
+                    }
+                    WasmQuery::Smart {
+                        contract_addr: _,
+                        msg,
+                    } => {
+                        let result = match from_json::<CollectionQueryMsg>(msg)
+                            .expect("Failed to find serialised type")
+                        {
+                            CollectionQueryMsg::NumTokens {} => ContractResult::Ok(
+                                to_json_binary(&self.response)
+                                    .expect("Failed to serialize num tokens response"),
+                            ),
+                            CollectionQueryMsg::OwnerOf { .. } => ContractResult::Ok(
+                                to_json_binary(&OwnerOfResponse {
+                                    owner: "executer".to_owned(),
+                                    approvals: vec![],
+                                })
+                                .expect("Failed to serialize owner response"),
+                            ),
+                            collection_query => unimplemented!("{:?}", collection_query),
+                        };
+                        SystemResult::Ok(result)
+                    }
+                    WasmQuery::ContractInfo { contract_addr: _ } => {
+                        let serialized =
+                            to_json_binary(&ContractInfoResponse::new(MOCK_CODE_ID, "creator"))
+                                .expect("Failed to serialize contract info response");
+                        SystemResult::Ok(ContractResult::Ok(serialized))
+                    }
+                    _ => unimplemented!("{:?}", wasm_query),
+                },
+                _ => self.base.handle_query(request),
+            }
+        }
+
+        pub fn new(base: MockQuerier<Empty>, response: NumTokensResponse) -> Self {
+            NumTokensMockQuerier { base, response }
+        }
+    }
+
+    #[test]
+    fn test_instantiate() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
+        let payment_params = PaymentParams::new(deployer.to_owned(), None);
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: payment_params.to_owned(),
+            config: Config::default(),
+        };
+
+        // Act
+        let result = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            mocked_msg_info,
+            instantiate_msg.to_owned(),
+        );
+
+        // Assert
+        assert!(result.is_ok(), "Failed to instantiate manager");
+        let received_response = result.unwrap();
+        let expected_response = Response::default().add_event(
+            Event::new("my-collection-manager")
+                .add_attribute("update-contract-version", "0.2.0")
+                .add_attribute("update-owner", deployer.to_owned())
+                .add_attribute("update-payment-params-beneficiary", deployer.to_owned())
+                .add_attribute("update-payment-params-mint-price", "none"),
+        );
+        assert_eq!(received_response, expected_response);
+        let saved_owner = OWNER
+            .load(&mocked_deps_mut.storage)
+            .expect("Failed to load owner");
+        assert_eq!(saved_owner, deployer);
+        let saved_payment_params = PAYMENT_PARAMS
+            .load(&mocked_deps_mut.storage)
+            .expect("Failed to load payment params");
+        assert_eq!(saved_payment_params, payment_params);
+        assert_contract_version(&mocked_deps_mut.storage, "my-collection-manager", "0.2.0")
+            .expect("Failed to assert contract version");
+        let contract_info = cw2::CONTRACT
+            .load(&mocked_deps_mut.storage)
+            .expect("Failed to load contract info");
+        assert_eq!(
+            contract_info,
+            ContractVersion {
+                contract: "my-collection-manager".to_owned(),
+                version: "0.2.0".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_pass_through() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            mocked_msg_info,
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let fund_sent = Coin {
+            denom: "gold".to_owned(),
+            amount: Uint128::from(335u128),
+        };
+        let mocked_msg_info = testing::mock_info(executer.as_ref(), &[fund_sent.to_owned()]);
+        let name = "alice".to_owned();
+        let owner = Addr::unchecked("owner");
+        let inner_msg = CollectionExecuteMsg::Mint {
+            token_id: name.to_owned(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        let execute_msg = ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: inner_msg.to_owned(),
+            mint_options: None,
+        };
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            mocked_msg_info,
+            execute_msg,
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass message through");
+        let received_response = contract_result.unwrap();
+        let expected_response = Response::default()
+            .add_message(BankMsg::Send {
+                to_address: executer.to_string(),
+                amount: vec![fund_sent],
+            })
+            .add_submessage(SubMsg {
+                id: super::reply_id(1, ReplyCode::PassThrough),
+                msg: CosmosMsg::<Empty>::Wasm(WasmMsg::Execute {
+                    contract_addr: "collection".to_owned(),
+                    msg: to_json_binary(&inner_msg).expect("Failed to serialize inner message"),
+                    funds: vec![],
+                }),
+                reply_on: ReplyOn::Success,
+                gas_limit: None,
+            })
+            .add_event(
+                Event::new("my-collection-manager").add_attribute("token-count-before", "3"),
+            );
+        assert_eq!(received_response, expected_response);
+    }
+
+    #[test]
+    fn test_pass_through_unregistered_collection() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            mocked_msg_info.to_owned(),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        let execute_msg = ExecuteMsg::PassThrough {
+            collection: "unknown".to_owned(),
+            message: CollectionExecuteMsg::Burn {
+                token_id: "alice".to_owned(),
+            },
+            mint_options: None,
+        };
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            mocked_msg_info,
+            execute_msg,
+        );
+
+        // Assert
+        match contract_result {
+            Err(ContractError::UnregisteredCollection { collection }) => {
+                assert_eq!(collection, "unknown")
+            }
+            _ => panic!("Expected UnregisteredCollection, got {:?}", contract_result),
+        }
+    }
+
+    #[test]
+    fn test_paid_mint_pass_through() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let beneficiary = Addr::unchecked("beneficiary");
+        let deployer = Addr::unchecked("deployer");
+        let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
+        let minting_price = Coin {
+            amount: Uint128::from(55u16),
+            denom: "silver".to_owned(),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(
+                beneficiary.to_owned(),
+                Some(minting_price.to_owned()),
+            ),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            mocked_msg_info,
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let extra_fund_sent = Coin {
+            denom: "gold".to_owned(),
+            amount: Uint128::from(335u128),
+        };
+        let fistful_silver = Coin {
+            amount: Uint128::from(30u16),
+            denom: "silver".to_owned(),
+        };
+        let mocked_msg_info = testing::mock_info(
+            executer.as_ref(),
+            &[
+                extra_fund_sent.to_owned(),
+                fistful_silver.to_owned(),
+                fistful_silver,
+            ],
+        );
+        let name = "alice".to_owned();
+        let owner = Addr::unchecked("owner");
+        let inner_msg = CollectionExecuteMsg::Mint {
+            token_id: name.to_owned(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        let execute_msg = ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: inner_msg.to_owned(),
+            mint_options: None,
+        };
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            mocked_msg_info.to_owned(),
+            execute_msg,
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass message through");
+        let received_response = contract_result.unwrap();
+        let expected_denom_change = Coin {
+            amount: Uint128::from(5u16),
+            denom: "silver".to_owned(),
+        };
+        let expected_response = Response::default()
+            .add_message(BankMsg::Send {
+                to_address: beneficiary.to_string(),
+                amount: vec![minting_price],
+            })
+            .add_message(BankMsg::Send {
+                to_address: mocked_msg_info.sender.to_string(),
+                amount: vec![extra_fund_sent, expected_denom_change],
+            })
+            .add_submessage(SubMsg {
+                id: super::reply_id(1, ReplyCode::PassThrough),
+                msg: CosmosMsg::<Empty>::Wasm(WasmMsg::Execute {
+                    contract_addr: "collection".to_owned(),
+                    msg: to_json_binary(&inner_msg).expect("Failed to serialize inner message"),
+                    funds: vec![],
+                }),
+                reply_on: ReplyOn::Success,
+                gas_limit: None,
+            })
+            .add_event(
+                Event::new("my-collection-manager")
+                    .add_attribute("token-count-before", "3")
+                    .add_attribute("mint-payment", "55silver"),
+            );
+        assert_eq!(received_response, expected_response);
+    }
+
+    #[test]
+    fn test_paid_mint_revenue_split() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let creator = Addr::unchecked("creator");
+        let treasury = Addr::unchecked("treasury");
+        let community = Addr::unchecked("community");
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams {
+                revenue_split: vec![
+                    RevenueShare {
+                        recipient: creator.to_owned(),
+                        weight: 2,
+                    },
+                    RevenueShare {
+                        recipient: treasury.to_owned(),
+                        weight: 1,
+                    },
+                ],
+                ..PaymentParams::new(
+                    community.to_owned(),
+                    Some(Coin {
+                        denom: "silver".to_owned(),
+                        amount: Uint128::from(100u16),
+                    }),
+                )
+            },
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let mocked_msg_info = testing::mock_info(
+            executer.as_ref(),
+            &[Coin {
+                denom: "silver".to_owned(),
+                amount: Uint128::from(100u16),
+            }],
+        );
+        let execute_msg = ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: "alice".to_owned(),
+                owner: executer.to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            mocked_msg_info,
+            execute_msg,
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass message through");
+        let received_response = contract_result.unwrap();
+        let expected_bank_msgs = vec![(creator, 66u16), (treasury, 33u16), (community, 1u16)]
+            .into_iter()
+            .map(|(recipient, amount)| {
+                SubMsg::new(BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: vec![Coin {
+                        denom: "silver".to_owned(),
+                        amount: Uint128::from(amount),
+                    }],
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(received_response.messages[..3], expected_bank_msgs[..]);
+        assert_eq!(received_response.messages.len(), 4);
+    }
+
+    #[test]
+    fn test_paid_mint_alternative_denom() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let silver_price = Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(55u16),
+        };
+        let gold_price = Coin {
+            denom: "gold".to_owned(),
+            amount: Uint128::from(10u16),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams {
+                alternative_mint_prices: vec![gold_price.to_owned()],
+                ..PaymentParams::new(beneficiary.to_owned(), Some(silver_price.to_owned()))
+            },
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let execute_msg = ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: "alice".to_owned(),
+                owner: executer.to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+        let short_funds = [Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(30u16),
+        }];
+        let short_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &short_funds),
+            execute_msg.to_owned(),
+        );
+        match short_result {
+            Err(ContractError::MissingPayment { accepted_payments }) => assert_eq!(
+                accepted_payments,
+                vec![silver_price.to_owned(), gold_price.to_owned()]
+            ),
+            _ => panic!("Expected MissingPayment, got {:?}", short_result),
+        }
+        let funds = [
+            short_funds[0].to_owned(),
+            Coin {
+                denom: "gold".to_owned(),
+                amount: Uint128::from(12u16),
+            },
+        ];
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info(executer.as_ref(), &funds),
+            execute_msg,
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass message through");
+        let received_response = contract_result.unwrap();
+        assert_eq!(
+            received_response.messages[..2],
+            [
+                SubMsg::new(BankMsg::Send {
+                    to_address: beneficiary.to_string(),
+                    amount: vec![gold_price],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: executer.to_string(),
+                    amount: vec![
+                        short_funds[0].to_owned(),
+                        Coin {
+                            denom: "gold".to_owned(),
+                            amount: Uint128::from(2u16),
+                        }
+                    ],
+                }),
+            ]
+        );
+        assert_eq!(
+            received_response.events,
+            vec![Event::new("my-collection-manager")
+                .add_attribute("token-count-before", "3")
+                .add_attribute("mint-payment", "10gold")]
+        );
+    }
+
+    #[test]
+    fn test_cw20_paid_mint_pass_through() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let token = Addr::unchecked("token");
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams {
+                cw20_mint_prices: vec![Cw20CoinVerified {
+                    address: token.to_owned(),
+                    amount: Uint128::from(50u16),
+                }],
+                ..PaymentParams::new(beneficiary.to_owned(), None)
+            },
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let inner_msg = CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: executer.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        let execute_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: executer.to_string(),
+            amount: Uint128::from(60u16),
+            msg: to_json_binary(&ReceiveMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: inner_msg.to_owned(),
+                mint_options: None,
+            })
+            .unwrap(),
+        });
+        let unaccepted_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(token.as_ref(), &[]),
+            execute_msg.to_owned(),
+        );
+        match unaccepted_result {
+            Err(ContractError::UnacceptedCw20 { token }) => assert_eq!(token, "token"),
+            _ => panic!("Expected UnacceptedCw20, got {:?}", unaccepted_result),
+        }
+        let _ = super::sudo(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            SudoMsg::AddAcceptedCw20 {
+                token: token.to_string(),
+            },
+        )
+        .expect("Failed to accept cw20");
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info(token.as_ref(), &[]),
+            execute_msg,
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass message through");
+        let received_response = contract_result.unwrap();
+        let transfer_msg = |recipient: &Addr, amount: u16| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: Uint128::from(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+        assert_eq!(
+            received_response.messages,
+            vec![
+                transfer_msg(&beneficiary, 50),
+                transfer_msg(&executer, 10),
+                SubMsg::reply_on_success(
+                    WasmMsg::Execute {
+                        contract_addr: "collection".to_owned(),
+                        msg: to_json_binary(&inner_msg).unwrap(),
+                        funds: vec![],
+                    },
+                    super::reply_id(1, ReplyCode::PassThrough),
+                ),
+            ]
+        );
+        assert_eq!(
+            received_response.events,
+            vec![Event::new("my-collection-manager")
+                .add_attribute("token-count-before", "3")
+                .add_attribute("mint-payment", "50token")]
+        );
+    }
+
+    #[test]
+    fn test_simulate_mint() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let artist = Addr::unchecked("artist");
+        let curator = Addr::unchecked("curator");
+        let minting_price = Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(55u16),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams {
+                revenue_split: vec![
+                    RevenueShare {
+                        recipient: artist.to_owned(),
+                        weight: 1,
+                    },
+                    RevenueShare {
+                        recipient: curator.to_owned(),
+                        weight: 2,
+                    },
+                ],
+                ..PaymentParams::new(beneficiary.to_owned(), Some(minting_price.to_owned()))
+            },
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let gold = Coin {
+            denom: "gold".to_owned(),
+            amount: Uint128::from(5u16),
+        };
+        let simulate = |funds: Vec<Coin>| {
+            let query_msg = QueryMsg::SimulateMint {
+                collection: "collection".to_owned(),
+                sender: "executer".to_owned(),
+                funds,
+                token_id: None,
+                years: None,
+            };
+            let binary = super::query(mocked_deps_mut.as_ref(), mocked_env.to_owned(), query_msg)
+                .expect("Failed to simulate mint");
+            from_json::<SimulateMintResponse>(binary).expect("Failed to deserialize response")
+        };
+
+        // Act
+        let paid_response = simulate(vec![
+            Coin {
+                denom: "silver".to_owned(),
+                amount: Uint128::from(70u16),
+            },
+            gold.to_owned(),
+        ]);
+        let short_response = simulate(vec![Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(30u16),
+        }]);
+
+        // Assert
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        assert_eq!(
+            paid_response,
+            SimulateMintResponse {
+                payment: Some(minting_price.to_owned()),
+                payouts: vec![
+                    MintPayout {
+                        recipient: artist,
+                        amount: silver(18),
+                    },
+                    MintPayout {
+                        recipient: curator,
+                        amount: silver(36),
+                    },
+                    MintPayout {
+                        recipient: beneficiary,
+                        amount: silver(1),
+                    },
+                ],
+                refund: vec![gold, silver(15)],
+                rebate: vec![],
+                error: None,
+            }
+        );
+        assert_eq!(
+            short_response,
+            SimulateMintResponse {
+                payment: None,
+                payouts: vec![],
+                refund: vec![],
+                rebate: vec![],
+                error: Some(
+                    ContractError::MissingPayment {
+                        accepted_payments: vec![minting_price],
+                    }
+                    .to_string()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_revenue_split() {
+        // Arrange
+        let recipient = Addr::unchecked("recipient");
+        let zero_weight = PaymentParams {
+            revenue_split: vec![RevenueShare {
+                recipient: recipient.to_owned(),
+                weight: 0,
+            }],
+            ..PaymentParams::new(recipient.to_owned(), None)
+        };
+        let duplicate = PaymentParams {
+            revenue_split: vec![
+                RevenueShare {
+                    recipient: recipient.to_owned(),
+                    weight: 1,
+                },
+                RevenueShare {
+                    recipient: recipient.to_owned(),
+                    weight: 1,
+                },
+            ],
+            ..PaymentParams::new(recipient.to_owned(), None)
+        };
+
+        // Act
+        let zero_weight_result = zero_weight.validate();
+        let duplicate_result = duplicate.validate();
+
+        // Assert
+        assert!(matches!(
+            zero_weight_result,
+            Err(ContractError::ZeroWeight { .. })
+        ));
+        assert!(matches!(
+            duplicate_result,
+            Err(ContractError::DuplicateRecipient { .. })
+        ));
+    }
+
+    #[test]
+    fn test_batch_pass_through_atomic() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let minting_price = Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(55u16),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(
+                beneficiary.to_owned(),
+                Some(minting_price.to_owned()),
+            ),
+            config: Config {
+                collection_code_id: None,
+                max_batch_size: Some(3),
+                mint_failure_policy: MintFailurePolicy::Revert,
+                accrue_revenue: false,
+                commit_reveal: None,
+            },
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let mint_item = |token_id: &str| PassThroughItem {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: token_id.to_owned(),
+                owner: executer.to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+        let burn_item = PassThroughItem {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Burn {
+                token_id: "carol".to_owned(),
+            },
+            mint_options: None,
+        };
+        let items = vec![mint_item("alice"), burn_item.to_owned(), mint_item("bob")];
+        let funds = [Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(120u16),
+        }];
+        let too_large_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &funds),
+            ExecuteMsg::BatchPassThrough {
+                items: vec![burn_item; 4],
+                mode: BatchMode::Atomic,
+            },
+        );
+        match too_large_result {
+            Err(ContractError::BatchTooLarge { max_batch_size }) => assert_eq!(max_batch_size, 3),
+            _ => panic!("Expected BatchTooLarge, got {:?}", too_large_result),
+        }
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info(executer.as_ref(), &funds),
+            ExecuteMsg::BatchPassThrough {
+                items: items.to_owned(),
+                mode: BatchMode::Atomic,
+            },
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass batch through");
+        let received_response = contract_result.unwrap();
+        let payout_msg = SubMsg::new(BankMsg::Send {
+            to_address: beneficiary.to_string(),
+            amount: vec![minting_price],
+        });
+        let onward_msg = |item: &PassThroughItem| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "collection".to_owned(),
+                msg: to_json_binary(&item.message).unwrap(),
+                funds: vec![],
+            })
+        };
+        assert_eq!(
+            received_response.messages,
+            vec![
+                payout_msg.to_owned(),
+                onward_msg(&items[0]),
+                onward_msg(&items[1]),
+                payout_msg,
+                onward_msg(&items[2]),
+                SubMsg::new(BankMsg::Send {
+                    to_address: executer.to_string(),
+                    amount: vec![Coin {
+                        denom: "silver".to_owned(),
+                        amount: Uint128::from(10u16),
+                    }],
+                }),
+            ]
+        );
+        assert_eq!(
+            received_response.events,
+            vec![Event::new("my-collection-manager")
+                .add_attribute("batch-size", "3")
+                .add_attribute("mint-payment", "110silver")]
+        );
+    }
+
+    #[test]
+    fn test_batch_pass_through_best_effort() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let minting_price = Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(55u16),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(
+                beneficiary.to_owned(),
+                Some(minting_price.to_owned()),
+            ),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let mint_msg = CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: executer.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        let items = vec![
+            PassThroughItem {
+                collection: "collection".to_owned(),
+                message: mint_msg.to_owned(),
+                mint_options: None,
+            },
+            PassThroughItem {
+                collection: "unknown".to_owned(),
+                message: mint_msg.to_owned(),
+                mint_options: None,
+            },
+        ];
+        let funds = [Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(60u16),
+        }];
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &funds),
+            ExecuteMsg::BatchPassThrough {
+                items,
+                mode: BatchMode::BestEffort,
+            },
+        );
+        assert!(contract_result.is_ok(), "Failed to pass batch through");
+        let received_response = contract_result.unwrap();
+        assert_eq!(
+            received_response.messages,
+            vec![
+                SubMsg::reply_always(
+                    WasmMsg::Execute {
+                        contract_addr: "collection".to_owned(),
+                        msg: to_json_binary(&mint_msg).unwrap(),
+                        funds: vec![],
+                    },
+                    super::reply_id(1, ReplyCode::BatchItem),
+                ),
+                SubMsg::new(BankMsg::Send {
+                    to_address: executer.to_string(),
+                    amount: vec![Coin {
+                        denom: "silver".to_owned(),
+                        amount: Uint128::from(5u16),
+                    }],
+                }),
+            ]
+        );
+        assert_eq!(
+            received_response.events,
+            vec![Event::new("my-collection-manager")
+                .add_attribute("batch-size", "2")
+                .add_attribute("mint-payment", "55silver")
+                .add_attribute(
+                    "batch-item-failed",
+                    "1:collection unknown is not registered"
+                )]
+        );
+        let reply = Reply {
+            id: super::reply_id(1, ReplyCode::BatchItem),
+            result: SubMsgResult::Err("token already claimed".to_owned()),
+        };
+
+        // Act
+        let contract_result = super::reply(mocked_deps_mut.as_mut(), mocked_env, reply);
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to handle batch item reply");
+        assert_eq!(
+            contract_result.unwrap(),
+            Response::default()
+                .add_message(BankMsg::Send {
+                    to_address: executer.to_string(),
+                    amount: vec![minting_price],
+                })
+                .add_event(
+                    Event::new("my-collection-manager")
+                        .add_attribute("batch-item-failed", "0:token already claimed")
+                )
+        );
+        assert!(REPLY_CONTEXTS.is_empty(&mocked_deps_mut.storage));
+    }
+
+    #[test]
+    fn test_batch_best_effort_rejected_items() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams::new(
+                    Addr::unchecked("beneficiary"),
+                    Some(silver(55)),
+                ),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::SetMintLimits {
+                collection: "collection".to_owned(),
+                limits: MintLimits {
+                    per_address: Some(1),
+                    max_supply: None,
+                },
+            },
+        )
+        .expect("Failed to set mint limits");
+        let mint_item = |token_id: &str| PassThroughItem {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: token_id.to_owned(),
+                owner: "executer".to_owned(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info("executer", &[silver(110)]),
+            ExecuteMsg::BatchPassThrough {
+                items: vec![mint_item("alice"), mint_item("bob")],
+                mode: BatchMode::BestEffort,
+            },
+        );
+
+        // Assert
+        let received_response = contract_result.expect("Failed to pass batch through");
+        assert_eq!(
+            received_response.messages,
+            vec![
+                SubMsg::reply_always(
+                    WasmMsg::Execute {
+                        contract_addr: "collection".to_owned(),
+                        msg: to_json_binary(&mint_item("alice").message)
+                            .expect("Failed to serialize mint"),
+                        funds: vec![],
+                    },
+                    super::reply_id(1, ReplyCode::BatchItem),
+                ),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "executer".to_owned(),
+                    amount: vec![silver(55)],
+                }),
+            ]
+        );
+        assert_eq!(
+            received_response.events,
+            vec![Event::new("my-collection-manager")
+                .add_attribute("batch-size", "2")
+                .add_attribute(
+                    "batch-item-failed",
+                    "1:minter reached the limit of 1 mints on the collection"
+                )
+                .add_attribute("mint-payment", "55silver")]
+        );
+        assert_eq!(
+            ADDRESS_MINT_COUNTS
+                .load(
+                    &mocked_deps_mut.storage,
+                    (&Addr::unchecked("collection"), &Addr::unchecked("executer"))
+                )
+                .expect("Failed to load address mint count"),
+            1
+        );
+    }
+
+    #[test]
+    fn test_reply_batch_item_refills_rebate_pool() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let collection = Addr::unchecked("collection");
+        let rebate = vec![MintPayout {
+            recipient: Addr::unchecked("executer"),
+            amount: Coin {
+                denom: "silver".to_owned(),
+                amount: Uint128::from(3u16),
+            },
+        }];
+        let reply_id = super::save_reply_context(
+            &mut mocked_deps_mut.storage,
+            ReplyCode::BatchItem,
+            &ReplyContext::BatchItem {
+                index: 0,
+                pass_through: PassThroughContext {
+                    sender: Addr::unchecked("executer"),
+                    collection: collection.to_owned(),
+                    token_id: Some("alice".to_owned()),
+                    payment: None,
+                    counted_mint: None,
+                    held_payouts: vec![],
+                    held_rebate: rebate,
+                },
+            },
+        )
+        .expect("Failed to save reply context");
+
+        // Act
+        let contract_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            testing::mock_env(),
+            Reply {
+                id: reply_id,
+                result: SubMsgResult::Err("token not found".to_owned()),
+            },
+        );
+
+        // Assert
+        assert_eq!(contract_result.expect("Failed to reply").messages, vec![]);
+        assert_eq!(
+            REBATE_POOLS
+                .load(&mocked_deps_mut.storage, (&collection, "silver"))
+                .expect("Failed to load rebate pool"),
+            Uint128::from(3u16)
+        );
+    }
+
+    #[test]
+    fn test_length_priced_batch_mint() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let length_price = |min_length: u32, amount: u16| LengthPrice {
+            min_length,
+            price: silver(amount),
+        };
+        let mut payment_params = PaymentParams {
+            length_prices: vec![
+                length_price(3, 500),
+                length_price(5, 10),
+                length_price(4, 100),
+            ],
+            ..PaymentParams::new(beneficiary.to_owned(), Some(silver(55)))
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: payment_params.to_owned(),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        payment_params.length_prices.push(length_price(4, 90));
+        let duplicate_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::UpdatePaymentParams(payment_params),
+        );
+        match duplicate_result {
+            Err(ContractError::DuplicateLengthPrice { min_length }) => assert_eq!(min_length, 4),
+            _ => panic!("Expected DuplicateLengthPrice, got {:?}", duplicate_result),
+        }
+        let executer = Addr::unchecked("executer");
+        let mint_item = |token_id: &str| PassThroughItem {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: token_id.to_owned(),
+                owner: executer.to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+        let items = vec![mint_item("ab"), mint_item("abc"), mint_item("alice")];
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info(executer.as_ref(), &[silver(600)]),
+            ExecuteMsg::BatchPassThrough {
+                items: items.to_owned(),
+                mode: BatchMode::Atomic,
+            },
+        );
+
+        // Assert
+        let received_response = contract_result.expect("Failed to pass batch through");
+        let payout_msg = |amount: u16| {
+            SubMsg::new(BankMsg::Send {
+                to_address: beneficiary.to_string(),
+                amount: vec![silver(amount)],
+            })
+        };
+        let onward_msg = |item: &PassThroughItem| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "collection".to_owned(),
+                msg: to_json_binary(&item.message).unwrap(),
+                funds: vec![],
+            })
+        };
+        assert_eq!(
+            received_response.messages,
+            vec![
+                payout_msg(55),
+                onward_msg(&items[0]),
+                payout_msg(500),
+                onward_msg(&items[1]),
+                payout_msg(10),
+                onward_msg(&items[2]),
+                SubMsg::new(BankMsg::Send {
+                    to_address: executer.to_string(),
+                    amount: vec![silver(35)],
+                }),
+            ]
+        );
+        assert_eq!(
+            received_response.events,
+            vec![Event::new("my-collection-manager")
+                .add_attribute("batch-size", "3")
+                .add_attribute("mint-payment", "565silver")]
+        );
+    }
+
+    #[test]
+    fn test_length_prices_without_mint_price() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams {
+                    length_prices: vec![LengthPrice {
+                        min_length: 3,
+                        price: Coin {
+                            denom: "silver".to_owned(),
+                            amount: Uint128::from(500u16),
+                        },
+                    }],
+                    ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+                },
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info("executer", &[]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: CollectionExecuteMsg::Mint {
+                    token_id: "ab".to_owned(),
+                    owner: "executer".to_owned(),
+                    token_uri: None,
+                    extension: None,
+                },
+                mint_options: None,
+            },
+        );
+
+        // Assert
+        match contract_result {
+            Err(ContractError::TokenIdBelowLengthPrices { min_length }) => {
+                assert_eq!(min_length, 3)
+            }
+            _ => panic!(
+                "Expected TokenIdBelowLengthPrices, got {:?}",
+                contract_result
+            ),
+        }
+    }
+
+    #[test]
+    fn test_quotes_normalize_token_ids() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams {
+                    length_prices: vec![
+                        LengthPrice {
+                            min_length: 1,
+                            price: silver(50),
+                        },
+                        LengthPrice {
+                            min_length: 3,
+                            price: silver(10),
+                        },
+                    ],
+                    ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+                },
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::SetTokenIdPolicy {
+                collection: "collection".to_owned(),
+                policy: TokenIdPolicy {
+                    charset: None,
+                    min_length: None,
+                    max_length: None,
+                    force_lowercase: true,
+                    reserved: vec![],
+                },
+            },
+        )
+        .expect("Failed to set token id policy");
+        // Lowercases to three characters, "i" and a combining dot, then "a".
+        let token_id = "\u{130}A".to_owned();
+
+        // Act
+        let simulated = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::SimulateMint {
+                collection: "collection".to_owned(),
+                sender: "executer".to_owned(),
+                funds: vec![silver(50)],
+                token_id: Some(token_id.to_owned()),
+                years: None,
+            },
+        );
+        let current_price = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::CurrentMintPrice {
+                collection: "collection".to_owned(),
+                token_id: Some(token_id),
+            },
+        );
+
+        // Assert
+        let simulated = from_json::<SimulateMintResponse>(simulated.expect("Failed to simulate"))
+            .expect("Failed to deserialize simulation");
+        assert_eq!(simulated.payment, Some(silver(10)));
+        assert_eq!(simulated.refund, vec![silver(40)]);
+        assert_eq!(
+            from_json::<CurrentMintPriceResponse>(
+                current_price.expect("Failed to query current mint price")
+            )
+            .expect("Failed to deserialize current mint price")
+            .prices,
+            vec![silver(10)]
+        );
+    }
+
+    #[test]
+    fn test_mint_failure_refund() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let minting_price = Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(50u16),
+        };
+        let mut config = Config {
+            collection_code_id: None,
+            max_batch_size: None,
+            mint_failure_policy: MintFailurePolicy::Refund { fee_bps: 10_001 },
+            accrue_revenue: false,
+            commit_reveal: None,
+        };
+        let mut instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(
+                beneficiary.to_owned(),
+                Some(minting_price.to_owned()),
+            ),
+            config: config.to_owned(),
+        };
+        let invalid_result = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg.to_owned(),
+        );
+        match invalid_result {
+            Err(ContractError::InvalidFeeBps { fee_bps }) => assert_eq!(fee_bps, 10_001),
+            _ => panic!("Expected InvalidFeeBps, got {:?}", invalid_result),
+        }
+        config.mint_failure_policy = MintFailurePolicy::Refund { fee_bps: 1_000 };
+        instantiate_msg.config = config;
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let executer = Addr::unchecked("executer");
+        let inner_msg = CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: executer.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        let execute_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &[minting_price.to_owned()]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: inner_msg.to_owned(),
+                mint_options: None,
+            },
+        );
+        assert_eq!(
+            execute_result
+                .expect("Failed to pass message through")
+                .messages,
+            vec![SubMsg::reply_always(
+                WasmMsg::Execute {
+                    contract_addr: "collection".to_owned(),
+                    msg: to_json_binary(&inner_msg).unwrap(),
+                    funds: vec![],
+                },
+                super::reply_id(1, ReplyCode::PassThrough),
+            )]
+        );
+        let reply = Reply {
+            id: super::reply_id(1, ReplyCode::PassThrough),
+            result: SubMsgResult::Err("token_id already claimed".to_owned()),
+        };
+
+        // Act
+        let contract_result = super::reply(mocked_deps_mut.as_mut(), mocked_env, reply);
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to handle failed mint");
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        assert_eq!(
+            contract_result.unwrap(),
+            Response::default()
+                .add_message(BankMsg::Send {
+                    to_address: beneficiary.to_string(),
+                    amount: vec![silver(5)],
+                })
+                .add_message(BankMsg::Send {
+                    to_address: executer.to_string(),
+                    amount: vec![silver(45)],
+                })
+                .add_event(
+                    Event::new("my-collection-manager")
+                        .add_attribute("mint-failed", "collection")
+                        .add_attribute("mint-failed-error", "token_id already claimed")
+                        .add_attribute("mint-failed-token-id", "alice")
+                        .add_attribute("mint-fee", "5silver")
+                        .add_attribute("mint-refund", "45silver")
+                )
+        );
+        assert!(REPLY_CONTEXTS.is_empty(&mocked_deps_mut.storage));
+        assert_eq!(
+            COLLECTION_MINT_COUNTS
+                .load(&mocked_deps_mut.storage, &Addr::unchecked("collection"))
+                .expect("Failed to load mint count"),
+            0
+        );
+    }
+
+    #[test]
+    fn test_mint_phase_allowlist() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let beneficiary = Addr::unchecked("beneficiary");
+        let deployer = Addr::unchecked("deployer");
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(
+                beneficiary.to_owned(),
+                Some(Coin {
+                    amount: Uint128::from(55u16),
+                    denom: "silver".to_owned(),
+                }),
+            ),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let leaf = |address: &str| Sha256::digest(address.as_bytes()).to_vec();
+        let (executer_leaf, ally_leaf) = (leaf("executer"), leaf("ally"));
+        let merkle_root = if executer_leaf < ally_leaf {
+            Sha256::digest([executer_leaf.as_slice(), ally_leaf.as_slice()].concat())
+        } else {
+            Sha256::digest([ally_leaf.as_slice(), executer_leaf.as_slice()].concat())
+        };
+        let presale_price = Coin {
+            amount: Uint128::from(20u16),
+            denom: "silver".to_owned(),
+        };
+        let public_price = Coin {
+            amount: Uint128::from(55u16),
+            denom: "silver".to_owned(),
+        };
+        let presale = MintPhase {
+            name: "presale".to_owned(),
+            start: mocked_env.block.time,
+            end: mocked_env.block.time.plus_seconds(3_600),
+            merkle_root: Some(HexBinary::from(merkle_root.to_vec())),
+            mint_price: Some(presale_price.to_owned()),
+            per_address_limit: Some(1),
+        };
+        let set_phases_msg = ExecuteMsg::SetMintPhases {
+            collection: "collection".to_owned(),
+            phases: vec![presale.to_owned()],
+        };
+        let set_phases_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            set_phases_msg,
+        )
+        .expect("Failed to set mint phases");
+        assert_eq!(
+            set_phases_response,
+            Response::default().add_event(
+                Event::new("my-collection-manager")
+                    .add_attribute("update-mint-phases", "collection")
+                    .add_attribute("update-mint-phases-names", "presale")
+            )
+        );
+        let mint_msg = |token_id: &str, merkle_proof: Vec<Vec<u8>>| ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: token_id.to_owned(),
+                owner: "owner".to_owned(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: Some(MintOptions {
+                merkle_proof: merkle_proof.into_iter().map(HexBinary::from).collect(),
+                years: None,
+            }),
+        };
+        let executer_info = testing::mock_info("executer", &[presale_price.to_owned()]);
+
+        // Act
+        let outsider_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("outsider", &[presale_price.to_owned()]),
+            mint_msg("alice", vec![ally_leaf.to_owned()]),
+        );
+        let missing_proof_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            executer_info.to_owned(),
+            mint_msg("alice", vec![]),
+        );
+        let first_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            executer_info.to_owned(),
+            mint_msg("alice", vec![ally_leaf.to_owned()]),
+        );
+        let second_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            executer_info,
+            mint_msg("bob", vec![ally_leaf]),
+        );
+        let mut after_env = mocked_env.to_owned();
+        after_env.block.time = presale.end;
+        let after_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            after_env,
+            testing::mock_info("outsider", &[public_price.to_owned()]),
+            mint_msg("carol", vec![]),
+        );
+
+        // Assert
+        for contract_result in [outsider_result, missing_proof_result] {
+            match contract_result {
+                Err(ContractError::InvalidMerkleProof { phase }) => assert_eq!(phase, "presale"),
+                _ => panic!("Expected InvalidMerkleProof, got {:?}", contract_result),
+            }
+        }
+        let first_response = first_result.expect("Failed to mint in presale");
+        assert_eq!(
+            first_response.messages[0],
+            SubMsg::new(BankMsg::Send {
+                to_address: beneficiary.to_string(),
+                amount: vec![presale_price],
+            })
+        );
+        match second_result {
+            Err(ContractError::MintPhaseLimitReached { phase, limit }) => {
+                assert_eq!(phase, "presale");
+                assert_eq!(limit, 1);
+            }
+            _ => panic!("Expected MintPhaseLimitReached, got {:?}", second_result),
+        }
+        let after_response = after_result.expect("Failed to mint after the presale");
+        assert_eq!(
+            after_response.messages[0],
+            SubMsg::new(BankMsg::Send {
+                to_address: beneficiary.to_string(),
+                amount: vec![public_price],
+            })
+        );
+    }
+
+    #[test]
+    fn test_mint_phase_queries() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let public = MintPhase {
+            name: "public".to_owned(),
+            start: mocked_env.block.time.minus_seconds(60),
+            end: mocked_env.block.time.plus_seconds(60),
+            merkle_root: None,
+            mint_price: None,
+            per_address_limit: Some(3),
+        };
+        let _ = super::sudo(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            SudoMsg::SetMintPhases {
+                collection: "collection".to_owned(),
+                phases: vec![public.to_owned()],
+            },
+        )
+        .expect("Failed to set mint phases");
+        PHASE_MINT_COUNTS
+            .save(
+                &mut mocked_deps_mut.storage,
+                (
+                    &Addr::unchecked("collection"),
+                    "public",
+                    &Addr::unchecked("executer"),
+                ),
+                &2,
+            )
+            .expect("Failed to save mint count");
+        let mut later_env = mocked_env.to_owned();
+        later_env.block.time = public.end;
+
+        // Act
+        let active_phase = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::ActiveMintPhase {
+                collection: "collection".to_owned(),
+            },
+        );
+        let allowance = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::MintAllowance {
+                collection: "collection".to_owned(),
+                address: "executer".to_owned(),
+            },
+        );
+        let later_allowance = super::query(
+            mocked_deps_mut.as_ref(),
+            later_env,
+            QueryMsg::MintAllowance {
+                collection: "collection".to_owned(),
+                address: "executer".to_owned(),
+            },
+        );
+        let unrestricted_allowance = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::MintAllowance {
+                collection: "other".to_owned(),
+                address: "executer".to_owned(),
+            },
+        );
+
+        // Assert
+        assert_eq!(
+            from_json::<ActiveMintPhaseResponse>(
+                active_phase.expect("Failed to query active mint phase")
+            )
+            .expect("Failed to deserialize active mint phase"),
+            ActiveMintPhaseResponse {
+                phase: Some(public)
+            }
+        );
+        for (query_result, expected_allowance) in [
+            (
+                allowance,
+                MintAllowanceResponse {
+                    phase: Some("public".to_owned()),
+                    minted: 2,
+                    remaining: Some(1),
+                },
+            ),
+            (
+                later_allowance,
+                MintAllowanceResponse {
+                    phase: None,
+                    minted: 0,
+                    remaining: None,
+                },
+            ),
+            (
+                unrestricted_allowance,
+                MintAllowanceResponse {
+                    phase: None,
+                    minted: 0,
+                    remaining: None,
+                },
+            ),
+        ] {
+            assert_eq!(
+                from_json::<MintAllowanceResponse>(
+                    query_result.expect("Failed to query mint allowance")
+                )
+                .expect("Failed to deserialize mint allowance"),
+                expected_allowance
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_mint_phases() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let phase = |name: &str, start: u64, end: u64| MintPhase {
+            name: name.to_owned(),
+            start: mocked_env.block.time.plus_seconds(start),
+            end: mocked_env.block.time.plus_seconds(end),
+            merkle_root: None,
+            mint_price: None,
+            per_address_limit: None,
+        };
+        let set_phases = |phases: Vec<MintPhase>| SudoMsg::SetMintPhases {
+            collection: "collection".to_owned(),
+            phases,
+        };
+
+        // Act
+        let empty_window_result = super::sudo(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            set_phases(vec![phase("presale", 10, 10)]),
+        );
+        let overlap_result = super::sudo(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            set_phases(vec![phase("presale", 0, 10), phase("public", 9, 20)]),
+        );
+        let short_root_result = super::sudo(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            set_phases(vec![MintPhase {
+                merkle_root: Some(HexBinary::from(vec![1, 2, 3])),
+                ..phase("presale", 0, 10)
+            }]),
+        );
+
+        // Assert
+        match empty_window_result {
+            Err(ContractError::InvalidMintPhaseWindow { name }) => assert_eq!(name, "presale"),
+            _ => panic!(
+                "Expected InvalidMintPhaseWindow, got {:?}",
+                empty_window_result
+            ),
+        }
+        match overlap_result {
+            Err(ContractError::OverlappingMintPhases { name, other }) => {
+                assert_eq!(name, "public");
+                assert_eq!(other, "presale");
+            }
+            _ => panic!("Expected OverlappingMintPhases, got {:?}", overlap_result),
+        }
+        match short_root_result {
+            Err(ContractError::InvalidMerkleRoot { name }) => assert_eq!(name, "presale"),
+            _ => panic!("Expected InvalidMerkleRoot, got {:?}", short_root_result),
+        }
+        assert!(!MINT_PHASES.has(&mocked_deps_mut.storage, &Addr::unchecked("collection")));
+    }
+
+    #[test]
+    fn test_mint_limits() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let limits = MintLimits {
+            per_address: Some(1),
+            max_supply: Some(2),
+        };
+        let zero_limits_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::SetMintLimits {
+                collection: "collection".to_owned(),
+                limits: MintLimits {
+                    per_address: Some(0),
+                    max_supply: None,
+                },
+            },
+        );
+        match zero_limits_result {
+            Err(ContractError::ZeroMintLimit) => {}
+            _ => panic!("Expected ZeroMintLimit, got {:?}", zero_limits_result),
+        }
+        let set_limits_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::SetMintLimits {
+                collection: "collection".to_owned(),
+                limits: limits.to_owned(),
+            },
+        )
+        .expect("Failed to set mint limits");
+        assert_eq!(
+            set_limits_response,
+            Response::default().add_event(
+                Event::new("my-collection-manager")
+                    .add_attribute("update-mint-limits", "collection")
+                    .add_attribute("update-mint-limits-per-address", "1")
+                    .add_attribute("update-mint-limits-max-supply", "2")
+            )
+        );
+        let mint_msg = |token_id: &str| ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: token_id.to_owned(),
+                owner: "owner".to_owned(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+
+        // Act
+        let first_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            mint_msg("alice"),
+        );
+        let second_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            mint_msg("bob"),
+        );
+        let other_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("other", &[]),
+            mint_msg("bob"),
+        );
+        let sold_out_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("latecomer", &[]),
+            mint_msg("carol"),
+        );
+        let quota = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::MintQuota {
+                collection: "collection".to_owned(),
+                address: "latecomer".to_owned(),
+            },
+        );
+
+        // Assert
+        assert!(first_result.is_ok(), "Failed to mint within limits");
+        match second_result {
+            Err(ContractError::AddressMintLimitReached { limit }) => assert_eq!(limit, 1),
+            _ => panic!("Expected AddressMintLimitReached, got {:?}", second_result),
+        }
+        assert!(other_result.is_ok(), "Failed to mint within limits");
+        match sold_out_result {
+            Err(ContractError::MaxSupplyReached { max_supply }) => assert_eq!(max_supply, 2),
+            _ => panic!("Expected MaxSupplyReached, got {:?}", sold_out_result),
+        }
+        assert_eq!(
+            from_json::<MintQuotaResponse>(quota.expect("Failed to query mint quota"))
+                .expect("Failed to deserialize mint quota"),
+            MintQuotaResponse {
+                limits,
+                minted_by_address: 0,
+                total_minted: 2,
+                remaining_for_address: Some(1),
+                remaining_supply: Some(0),
+            }
+        );
+    }
+
+    #[test]
+    fn test_token_id_policy() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let invalid_policy_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::SetTokenIdPolicy {
+                collection: "collection".to_owned(),
+                policy: TokenIdPolicy {
+                    min_length: Some(5),
+                    max_length: Some(3),
+                    ..TokenIdPolicy::default()
+                },
+            },
+        );
+        match invalid_policy_result {
+            Err(ContractError::InvalidTokenIdLengths {
+                min_length,
+                max_length,
+            }) => assert_eq!((min_length, max_length), (5, 3)),
+            _ => panic!(
+                "Expected InvalidTokenIdLengths, got {:?}",
+                invalid_policy_result
+            ),
+        }
+        let set_policy_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::SetTokenIdPolicy {
+                collection: "collection".to_owned(),
+                policy: TokenIdPolicy {
+                    charset: Some(TokenIdCharset::Hostname),
+                    min_length: Some(3),
+                    max_length: Some(8),
+                    force_lowercase: true,
+                    reserved: vec!["Admin".to_owned()],
+                },
+            },
+        )
+        .expect("Failed to set token id policy");
+        assert_eq!(
+            set_policy_response,
+            Response::default().add_event(
+                Event::new("my-collection-manager")
+                    .add_attribute("update-token-id-policy", "collection")
+                    .add_attribute("update-token-id-policy-charset", "hostname")
+                    .add_attribute("update-token-id-policy-min-length", "3")
+                    .add_attribute("update-token-id-policy-max-length", "8")
+                    .add_attribute("update-token-id-policy-force-lowercase", "true")
+                    .add_attribute("update-token-id-policy-reserved", "Admin")
+            )
+        );
+        let mint_msg = |token_id: &str| CollectionExecuteMsg::Mint {
+            token_id: token_id.to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        };
+        let pass_through = |token_id: &str| ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: mint_msg(token_id),
+            mint_options: None,
+        };
+
+        // Act
+        let normalized_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            pass_through("My-Name"),
+        );
+        let short_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            pass_through("ab"),
+        );
+        let long_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            pass_through("much-too-long"),
+        );
+        let invalid_char_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            pass_through("my_name"),
+        );
+        let reserved_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            pass_through("ADMIN"),
+        );
+        let valid_query = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::ValidateTokenId {
+                collection: "collection".to_owned(),
+                token_id: "Alice".to_owned(),
+            },
+        );
+        let invalid_query = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::ValidateTokenId {
+                collection: "collection".to_owned(),
+                token_id: "admin".to_owned(),
+            },
+        );
+        let reserved_quote = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::SimulateMint {
+                collection: "collection".to_owned(),
+                sender: "executer".to_owned(),
+                funds: vec![],
+                token_id: Some("Admin".to_owned()),
+                years: None,
+            },
+        );
+
+        // Assert
+        let normalized_response =
+            normalized_response.expect("Failed to mint a token id the policy normalizes");
+        match &normalized_response.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                msg,
+                &to_json_binary(&mint_msg("my-name")).expect("Failed to serialize mint")
+            ),
+            other => panic!("Expected a wasm execute message, got {:?}", other),
+        }
+        match short_result {
+            Err(ContractError::TokenIdTooShort {
+                token_id,
+                min_length,
+            }) => assert_eq!((token_id.as_str(), min_length), ("ab", 3)),
+            _ => panic!("Expected TokenIdTooShort, got {:?}", short_result),
+        }
+        match long_result {
+            Err(ContractError::TokenIdTooLong {
+                token_id,
+                max_length,
+            }) => assert_eq!((token_id.as_str(), max_length), ("much-too-long", 8)),
+            _ => panic!("Expected TokenIdTooLong, got {:?}", long_result),
+        }
+        match invalid_char_result {
+            Err(ContractError::TokenIdInvalidChar {
+                token_id,
+                character,
+            }) => assert_eq!((token_id.as_str(), character), ("my_name", '_')),
+            _ => panic!("Expected TokenIdInvalidChar, got {:?}", invalid_char_result),
+        }
+        match reserved_result {
+            Err(ContractError::TokenIdReserved { token_id }) => assert_eq!(token_id, "admin"),
+            _ => panic!("Expected TokenIdReserved, got {:?}", reserved_result),
+        }
+        assert_eq!(
+            from_json::<ValidateTokenIdResponse>(valid_query.expect("Failed to validate token id"))
+                .expect("Failed to deserialize token id validation"),
+            ValidateTokenIdResponse {
+                token_id: Some("alice".to_owned()),
+                error: None,
+            }
+        );
+        assert_eq!(
+            from_json::<ValidateTokenIdResponse>(
+                invalid_query.expect("Failed to validate token id")
+            )
+            .expect("Failed to deserialize token id validation"),
+            ValidateTokenIdResponse {
+                token_id: None,
+                error: Some("token id admin is reserved".to_owned()),
+            }
+        );
+        assert_eq!(
+            from_json::<SimulateMintResponse>(reserved_quote.expect("Failed to simulate mint"))
+                .expect("Failed to deserialize simulated mint")
+                .error,
+            Some("token id admin is reserved".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_commit_reveal_mint() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let committed_at = mocked_env.block.height;
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let executer = Addr::unchecked("executer");
+        let deposit = Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(10u16),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(beneficiary.to_owned(), None),
+            config: Config {
+                accrue_revenue: false,
+                commit_reveal: Some(CommitRevealConfig {
+                    min_blocks: 2,
+                    max_blocks: 10,
+                    deposit: Some(deposit.to_owned()),
+                    deposit_refund: CommitDepositRefund::OnReveal,
+                    required: true,
+                }),
+                ..Config::default()
+            },
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let mint_msg = |token_id: &str| CollectionExecuteMsg::Mint {
+            token_id: token_id.to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        };
+        let reveal_msg = |token_id: &str, salt: &[u8]| ExecuteMsg::RevealMint {
+            collection: "collection".to_owned(),
+            message: mint_msg(token_id),
+            salt: HexBinary::from(salt),
+            mint_options: None,
+        };
+        let commitment = mint_commitment("alice", "owner", b"salt", executer.as_str());
+        let other_commitment = mint_commitment("bob", "owner", b"salt", executer.as_str());
+        let direct_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &[]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: mint_msg("alice"),
+                mint_options: None,
+            },
+        );
+        match direct_result {
+            Err(ContractError::CommitRevealRequired) => {}
+            _ => panic!("Expected CommitRevealRequired, got {:?}", direct_result),
+        }
+        let no_deposit_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &[]),
+            ExecuteMsg::CommitMint {
+                commitment: commitment.to_owned(),
+            },
+        );
+        match no_deposit_result {
+            Err(ContractError::InvalidCommitDeposit { deposit: expected }) => {
+                assert_eq!(expected, vec![deposit.to_owned()])
+            }
+            _ => panic!("Expected InvalidCommitDeposit, got {:?}", no_deposit_result),
+        }
+        for commitment in [&commitment, &other_commitment] {
+            let _ = super::execute(
+                mocked_deps_mut.as_mut(),
+                mocked_env.to_owned(),
+                testing::mock_info(executer.as_ref(), &[deposit.to_owned()]),
+                ExecuteMsg::CommitMint {
+                    commitment: commitment.to_owned(),
+                },
+            )
+            .expect("Failed to commit mint");
+        }
+
+        // Act
+        let early_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &[]),
+            reveal_msg("alice", b"salt"),
+        );
+        mocked_env.block.height = committed_at + 2;
+        let wrong_salt_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &[]),
+            reveal_msg("alice", b"pepper"),
+        );
+        let reveal_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &[]),
+            reveal_msg("alice", b"salt"),
+        );
+        let pending_query = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::MintCommitment {
+                commitment: other_commitment.to_owned(),
+            },
+        );
+        mocked_env.block.height = committed_at + 10;
+        let expired_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(executer.as_ref(), &[]),
+            reveal_msg("bob", b"salt"),
+        );
+        let clear_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            ExecuteMsg::ClearExpiredCommitments { limit: None },
+        );
+        let cleared_query = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::MintCommitment {
+                commitment: other_commitment,
+            },
+        );
+
+        // Assert
+        match early_result {
+            Err(ContractError::RevealTooEarly { reveal_from }) => {
+                assert_eq!(reveal_from, committed_at + 2)
+            }
+            _ => panic!("Expected RevealTooEarly, got {:?}", early_result),
+        }
+        match wrong_salt_result {
+            Err(ContractError::UnknownCommitment) => {}
+            _ => panic!("Expected UnknownCommitment, got {:?}", wrong_salt_result),
+        }
+        let reveal_response = reveal_response.expect("Failed to reveal mint");
+        assert_eq!(reveal_response.messages.len(), 2);
+        match &reveal_response.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                msg,
+                &to_json_binary(&mint_msg("alice")).expect("Failed to serialize mint")
+            ),
+            other => panic!("Expected a wasm execute message, got {:?}", other),
+        }
+        assert_eq!(
+            reveal_response.messages[1],
+            SubMsg::new(BankMsg::Send {
+                to_address: executer.to_string(),
+                amount: vec![deposit.to_owned()],
+            })
+        );
+        assert_eq!(
+            from_json::<MintCommitmentResponse>(pending_query.expect("Failed to query commitment"))
+                .expect("Failed to deserialize commitment"),
+            MintCommitmentResponse {
+                commitment: Some(MintCommitment {
+                    sender: executer.to_owned(),
+                    height: committed_at,
+                    deposit: Some(deposit.to_owned()),
+                }),
+                revealable: true,
+                expired: false,
+            }
+        );
+        match expired_result {
+            Err(ContractError::CommitmentExpired { expired_at }) => {
+                assert_eq!(expired_at, committed_at + 10)
+            }
+            _ => panic!("Expected CommitmentExpired, got {:?}", expired_result),
+        }
+        assert_eq!(
+            clear_response.expect("Failed to clear expired commitments"),
+            Response::default()
+                .add_message(BankMsg::Send {
+                    to_address: beneficiary.to_string(),
+                    amount: vec![deposit],
+                })
+                .add_event(
+                    Event::new("my-collection-manager")
+                        .add_attribute("clear-expired-commitments", "1")
+                )
+        );
+        assert_eq!(
+            from_json::<MintCommitmentResponse>(cleared_query.expect("Failed to query commitment"))
+                .expect("Failed to deserialize commitment"),
+            MintCommitmentResponse {
+                commitment: None,
+                revealable: false,
+                expired: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_forfeited_commit_deposits() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let committed_at = mocked_env.block.height;
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams::new(Addr::unchecked("beneficiary"), None),
+                config: Config {
+                    accrue_revenue: true,
+                    commit_reveal: Some(CommitRevealConfig {
+                        min_blocks: 2,
+                        max_blocks: 10,
+                        deposit: Some(silver(10)),
+                        deposit_refund: CommitDepositRefund::Never,
+                        required: true,
+                    }),
+                    ..Config::default()
+                },
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        COLLECTION_PAYMENT_PARAMS
+            .save(
+                &mut mocked_deps_mut.storage,
+                &Addr::unchecked("collection"),
+                &PaymentParams {
+                    revenue_split: vec![
+                        RevenueShare {
+                            recipient: Addr::unchecked("artist"),
+                            weight: 1,
+                        },
+                        RevenueShare {
+                            recipient: Addr::unchecked("curator"),
+                            weight: 1,
+                        },
+                    ],
+                    ..PaymentParams::new(Addr::unchecked("artist"), None)
+                },
+            )
+            .expect("Failed to save collection payment params");
+        for token_id in ["alice", "bob"] {
+            let _ = super::execute(
+                mocked_deps_mut.as_mut(),
+                mocked_env.to_owned(),
+                testing::mock_info("executer", &[silver(10)]),
+                ExecuteMsg::CommitMint {
+                    commitment: mint_commitment(token_id, "owner", b"salt", "executer"),
+                },
+            )
+            .expect("Failed to commit mint");
+        }
+        let claimable = |deps: Deps, address: &str| {
+            from_json::<ClaimableResponse>(
+                super::query(
+                    deps,
+                    testing::mock_env(),
+                    QueryMsg::Claimable {
+                        address: address.to_owned(),
+                    },
+                )
+                .expect("Failed to query claimable"),
+            )
+            .expect("Failed to deserialize claimable")
+            .balances
+        };
+
+        // Act
+        mocked_env.block.height = committed_at + 2;
+        let reveal_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[]),
+            ExecuteMsg::RevealMint {
+                collection: "collection".to_owned(),
+                message: CollectionExecuteMsg::Mint {
+                    token_id: "alice".to_owned(),
+                    owner: "owner".to_owned(),
+                    token_uri: None,
+                    extension: None,
+                },
+                salt: HexBinary::from(b"salt".as_slice()),
+                mint_options: None,
+            },
+        );
+        mocked_env.block.height = committed_at + 10;
+        let clear_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            ExecuteMsg::ClearExpiredCommitments { limit: None },
+        );
+        let revenue = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::CollectionRevenue {
+                collection: "collection".to_owned(),
+            },
+        );
+
+        // Assert
+        let reveal_response = reveal_response.expect("Failed to reveal mint");
+        assert_eq!(reveal_response.messages.len(), 1);
+        assert!(matches!(
+            reveal_response.messages[0].msg,
+            CosmosMsg::Wasm(_)
+        ));
+        assert!(clear_response
+            .expect("Failed to clear expired commitments")
+            .messages
+            .is_empty());
+        assert_eq!(
+            claimable(mocked_deps_mut.as_ref(), "artist"),
+            vec![silver(5)]
+        );
+        assert_eq!(
+            claimable(mocked_deps_mut.as_ref(), "curator"),
+            vec![silver(5)]
+        );
+        assert_eq!(
+            claimable(mocked_deps_mut.as_ref(), "beneficiary"),
+            vec![silver(10)]
+        );
+        assert_eq!(
+            from_json::<CollectionRevenueResponse>(revenue.expect("Failed to query revenue"))
+                .expect("Failed to deserialize revenue")
+                .revenue,
+            vec![silver(10)]
+        );
+    }
+
+    #[test]
+    fn test_registrations() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let minted_at = mocked_env.block.time.seconds();
+        let deployer = Addr::unchecked("deployer");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams {
+                yearly_price: Some(silver(100)),
+                ..PaymentParams::new(beneficiary.to_owned(), Some(silver(50)))
+            },
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let mint_msg = ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: "alice".to_owned(),
+                owner: "owner".to_owned(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: Some(MintOptions {
+                merkle_proof: vec![],
+                years: Some(2),
+            }),
+        };
+        let expiry_query = |deps: Deps, env: Env| {
+            from_json::<ExpiryResponse>(
+                super::query(
+                    deps,
+                    env,
+                    QueryMsg::Expiry {
+                        collection: "collection".to_owned(),
+                        token_id: "alice".to_owned(),
+                    },
+                )
+                .expect("Failed to query expiry"),
+            )
+            .expect("Failed to deserialize expiry")
+        };
+        let simulated = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::SimulateMint {
+                collection: "collection".to_owned(),
+                sender: "executer".to_owned(),
+                funds: vec![silver(250)],
+                token_id: Some("alice".to_owned()),
+                years: Some(2),
+            },
+        );
+        assert_eq!(
+            from_json::<SimulateMintResponse>(simulated.expect("Failed to simulate mint"))
+                .expect("Failed to deserialize simulation")
+                .payment,
+            Some(silver(250))
+        );
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[silver(250)]),
+            mint_msg.to_owned(),
+        )
+        .expect("Failed to mint alice");
+
+        // Act
+        let double_mint_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[silver(250)]),
+            mint_msg,
+        );
+        let minted_expiry = expiry_query(mocked_deps_mut.as_ref(), mocked_env.to_owned());
+        let renew_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[silver(120)]),
+            ExecuteMsg::Renew {
+                collection: "collection".to_owned(),
+                token_id: "alice".to_owned(),
+                years: 1,
+            },
+        );
+        let early_reclaim_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::Reclaim {
+                collection: "collection".to_owned(),
+                token_id: "alice".to_owned(),
+                reassign: None,
+            },
+        );
+        let expiring = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::ExpiringTokens {
+                start: mocked_env.block.time,
+                end: mocked_env.block.time.plus_seconds(4 * SECONDS_PER_YEAR),
+                start_after: None,
+                limit: None,
+            },
+        );
+        mocked_env.block.time = Timestamp::from_seconds(minted_at + 3 * SECONDS_PER_YEAR);
+        let expired_expiry = expiry_query(mocked_deps_mut.as_ref(), mocked_env.to_owned());
+        let late_renew_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[silver(100)]),
+            ExecuteMsg::Renew {
+                collection: "collection".to_owned(),
+                token_id: "alice".to_owned(),
+                years: 1,
+            },
+        );
+        let unauthorized_reclaim_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            ExecuteMsg::Reclaim {
+                collection: "collection".to_owned(),
+                token_id: "alice".to_owned(),
+                reassign: None,
+            },
+        );
+        let reclaim_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::Reclaim {
+                collection: "collection".to_owned(),
+                token_id: "alice".to_owned(),
+                reassign: Some(Reassignment {
+                    owner: "newowner".to_owned(),
+                    years: 1,
+                }),
+            },
+        );
+        let reassigned_expiry = expiry_query(mocked_deps_mut.as_ref(), mocked_env.to_owned());
+        let burn_reply_id = super::reply_id(2, ReplyCode::Reclaim);
+        let burnt_response = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            Reply {
+                id: burn_reply_id,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    data: None,
+                    events: vec![],
+                }),
+            },
+        );
+        mocked_env.block.time = mocked_env.block.time.plus_seconds(2 * SECONDS_PER_YEAR);
+        let failed_burn_reply_id = super::reply_id(3, ReplyCode::Reclaim);
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::Reclaim {
+                collection: "collection".to_owned(),
+                token_id: "alice".to_owned(),
+                reassign: Some(Reassignment {
+                    owner: "thirdowner".to_owned(),
+                    years: 1,
+                }),
+            },
+        )
+        .expect("Failed to reclaim alice again");
+        let failed_burn_response = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            Reply {
+                id: failed_burn_reply_id,
+                result: SubMsgResult::Err("not an operator of the holder".to_owned()),
+            },
+        );
+        let unreassigned_expiry = expiry_query(mocked_deps_mut.as_ref(), mocked_env);
+
+        // Assert
+        match double_mint_result {
+            Err(ContractError::AlreadyRegistered {
+                token_id,
+                expires_at,
+            }) => assert_eq!(
+                (token_id.as_str(), expires_at.seconds()),
+                ("alice", minted_at + 2 * SECONDS_PER_YEAR)
+            ),
+            _ => panic!("Expected AlreadyRegistered, got {:?}", double_mint_result),
+        }
+        assert_eq!(
+            minted_expiry,
+            ExpiryResponse {
+                expires_at: Some(Timestamp::from_seconds(minted_at + 2 * SECONDS_PER_YEAR)),
+                expired: false,
+            }
+        );
+        let renewed_at = Timestamp::from_seconds(minted_at + 3 * SECONDS_PER_YEAR);
+        assert_eq!(
+            renew_response.expect("Failed to renew alice"),
+            Response::default()
+                .add_message(BankMsg::Send {
+                    to_address: beneficiary.to_string(),
+                    amount: vec![silver(100)],
+                })
+                .add_message(BankMsg::Send {
+                    to_address: "anyone".to_owned(),
+                    amount: vec![silver(20)],
+                })
+                .add_event(
+                    Event::new("my-collection-manager")
+                        .add_attribute("renew", "collection")
+                        .add_attribute("renew-token-id", "alice")
+                        .add_attribute("renew-expires-at", renewed_at.to_string())
+                        .add_attribute("renew-payment", "100silver")
+                )
+        );
+        match early_reclaim_result {
+            Err(ContractError::RegistrationNotExpired { expires_at, .. }) => {
+                assert_eq!(expires_at, renewed_at)
+            }
+            _ => panic!(
+                "Expected RegistrationNotExpired, got {:?}",
+                early_reclaim_result
+            ),
+        }
+        assert_eq!(
+            from_json::<ExpiringTokensResponse>(expiring.expect("Failed to query expiring tokens"))
+                .expect("Failed to deserialize expiring tokens"),
+            ExpiringTokensResponse {
+                registrations: vec![Registration {
+                    collection: Addr::unchecked("collection"),
+                    token_id: "alice".to_owned(),
+                    expires_at: renewed_at,
+                }],
+            }
+        );
+        assert_eq!(
+            expired_expiry,
+            ExpiryResponse {
+                expires_at: Some(renewed_at),
+                expired: true,
+            }
+        );
+        match late_renew_result {
+            Err(ContractError::RegistrationExpired { expires_at, .. }) => {
+                assert_eq!(expires_at, renewed_at)
+            }
+            _ => panic!("Expected RegistrationExpired, got {:?}", late_renew_result),
+        }
+        match unauthorized_reclaim_result {
+            Err(ContractError::Unauthorized) => {}
+            _ => panic!(
+                "Expected Unauthorized, got {:?}",
+                unauthorized_reclaim_result
+            ),
+        }
+        let reclaim_response = reclaim_response.expect("Failed to reclaim alice");
+        assert_eq!(
+            reclaim_response.messages,
+            vec![SubMsg::reply_always(
+                WasmMsg::Execute {
+                    contract_addr: "collection".to_owned(),
+                    msg: to_json_binary(&CollectionExecuteMsg::Burn {
+                        token_id: "alice".to_owned(),
+                    })
+                    .expect("Failed to serialize burn"),
+                    funds: vec![],
+                },
+                burn_reply_id,
+            )]
+        );
+        assert_eq!(
+            burnt_response.expect("Failed to reply to the burn"),
+            Response::default().add_message(WasmMsg::Execute {
+                contract_addr: "collection".to_owned(),
+                msg: to_json_binary(&CollectionExecuteMsg::Mint {
+                    token_id: "alice".to_owned(),
+                    owner: "newowner".to_owned(),
+                    token_uri: None,
+                    extension: None,
+                })
+                .expect("Failed to serialize mint"),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            reassigned_expiry,
+            ExpiryResponse {
+                expires_at: Some(Timestamp::from_seconds(
+                    renewed_at.seconds() + SECONDS_PER_YEAR
+                )),
+                expired: false,
+            }
+        );
+        assert_eq!(
+            failed_burn_response.expect("Failed to reply to the failed burn"),
+            Response::default().add_event(
+                Event::new("my-collection-manager")
+                    .add_attribute("reclaim-burn-failed", "alice")
+                    .add_attribute("reclaim-burn-error", "not an operator of the holder")
+            )
+        );
+        assert_eq!(
+            unreassigned_expiry,
+            ExpiryResponse {
+                expires_at: None,
+                expired: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_auction() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(Addr::unchecked("beneficiary"), Some(silver(10))),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let end = mocked_env.block.time.plus_seconds(100);
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::OpenAuction {
+                collection: "collection".to_owned(),
+                token_id: "gold".to_owned(),
+                reserve_price: silver(100),
+                end,
+                min_increment: Uint128::from(10u16),
+            },
+        )
+        .expect("Failed to open auction");
+        let bid = |deps: DepsMut, env: Env, bidder: &str, funds: &[Coin]| {
+            super::execute(
+                deps,
+                env,
+                testing::mock_info(bidder, funds),
+                ExecuteMsg::Bid { auction_id: 1 },
+            )
+        };
+
+        // Act
+        let below_reserve_result = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "alice",
+            &[silver(90)],
+        );
+        let wrong_denom_result = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "alice",
+            &[Coin {
+                denom: "gold".to_owned(),
+                amount: Uint128::from(100u16),
+            }],
+        );
+        let first_response = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "alice",
+            &[silver(100)],
+        );
+        let small_raise_result = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "bob",
+            &[silver(105)],
+        );
+        let outbid_response = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "bob",
+            &[silver(110)],
+        );
+        let direct_mint_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("alice", &[silver(10)]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: CollectionExecuteMsg::Mint {
+                    token_id: "gold".to_owned(),
+                    owner: "alice".to_owned(),
+                    token_uri: None,
+                    extension: None,
+                },
+                mint_options: None,
+            },
+        );
+        let active_auctions = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::ActiveAuctions {
+                start_after: None,
+                limit: None,
+            },
+        );
+        mocked_env.block.time = end;
+        let late_bid_result = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "carol",
+            &[silver(200)],
+        );
+        let settle_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info("anyone", &[]),
+            ExecuteMsg::SettleAuction { auction_id: 1 },
+        );
+
+        // Assert
+        match below_reserve_result {
+            Err(ContractError::BidTooLow { min_bid }) => assert_eq!(min_bid, silver(100)),
+            _ => panic!("Expected BidTooLow, got {:?}", below_reserve_result),
+        }
+        match wrong_denom_result {
+            Err(ContractError::InvalidBidFunds { denom }) => assert_eq!(denom, "silver"),
+            _ => panic!("Expected InvalidBidFunds, got {:?}", wrong_denom_result),
+        }
+        assert!(
+            first_response
+                .expect("Failed to bid as alice")
+                .messages
+                .is_empty(),
+            "Refunded a first bid"
+        );
+        match small_raise_result {
+            Err(ContractError::BidTooLow { min_bid }) => assert_eq!(min_bid, silver(110)),
+            _ => panic!("Expected BidTooLow, got {:?}", small_raise_result),
+        }
+        assert_eq!(
+            outbid_response.expect("Failed to outbid alice").messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: vec![silver(100)],
+            })]
+        );
+        match direct_mint_result {
+            Err(ContractError::TokenIdInAuction { token_id }) => assert_eq!(token_id, "gold"),
+            _ => panic!("Expected TokenIdInAuction, got {:?}", direct_mint_result),
+        }
+        let active_auctions = from_json::<ActiveAuctionsResponse>(
+            active_auctions.expect("Failed to query active auctions"),
+        )
+        .expect("Failed to deserialize active auctions");
+        assert_eq!(active_auctions.auctions.len(), 1);
+        assert_eq!(
+            active_auctions.auctions[0].highest_bid,
+            Some(Bid {
+                bidder: Addr::unchecked("bob"),
+                amount: Uint128::from(110u16),
+                placed_at: end.minus_seconds(100),
+            })
+        );
+        match late_bid_result {
+            Err(ContractError::AuctionEnded { auction_id }) => assert_eq!(auction_id, 1),
+            _ => panic!("Expected AuctionEnded, got {:?}", late_bid_result),
+        }
+        let settle_response = settle_response.expect("Failed to settle auction");
+        assert_eq!(settle_response.messages.len(), 1);
+        assert_eq!(settle_response.messages[0].reply_on, ReplyOn::Always);
+        match &settle_response.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                msg,
+                &to_json_binary(&CollectionExecuteMsg::Mint {
+                    token_id: "gold".to_owned(),
+                    owner: "bob".to_owned(),
+                    token_uri: None,
+                    extension: None,
+                })
+                .expect("Failed to serialize mint")
+            ),
+            other => panic!("Expected a wasm execute message, got {:?}", other),
+        }
+        match REPLY_CONTEXTS
+            .load(&mocked_deps_mut.storage, settle_response.messages[0].id)
+            .expect("Failed to load reply context")
+        {
+            ReplyContext::PassThrough(context) => assert_eq!(
+                context.held_payouts,
+                vec![MintPayout {
+                    recipient: Addr::unchecked("beneficiary"),
+                    amount: silver(110),
+                }]
+            ),
+            other => panic!("Expected a pass-through context, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_auction_registration_and_max_supply() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams {
+                    yearly_price: Some(silver(20)),
+                    ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+                },
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::SetMintLimits {
+                collection: "collection".to_owned(),
+                limits: MintLimits {
+                    per_address: None,
+                    max_supply: Some(1),
+                },
+            },
+        )
+        .expect("Failed to set mint limits");
+        let end = mocked_env.block.time.plus_seconds(100);
+        let open_auction = |deps: DepsMut, env: Env, token_id: &str, reserve_price: Coin| {
+            super::execute(
+                deps,
+                env,
+                testing::mock_info("deployer", &[]),
+                ExecuteMsg::OpenAuction {
+                    collection: "collection".to_owned(),
+                    token_id: token_id.to_owned(),
+                    reserve_price,
+                    end,
+                    min_increment: Uint128::from(10u16),
+                },
+            )
+        };
+        let open_response = open_auction(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "gold",
+            silver(100),
+        )
+        .expect("Failed to open auction");
+        let bid = |deps: DepsMut, env: Env, funds: &[Coin]| {
+            super::execute(
+                deps,
+                env,
+                testing::mock_info("alice", funds),
+                ExecuteMsg::Bid { auction_id: 1 },
+            )
+        };
+
+        // Act
+        let mismatched_open_result = open_auction(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "bronze",
+            Coin {
+                denom: "gold".to_owned(),
+                amount: Uint128::from(100u16),
+            },
+        );
+        let unregistered_bid_result = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            &[silver(100)],
+        );
+        let bid_result = bid(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            &[silver(120)],
+        );
+        let mint_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[silver(20)]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: CollectionExecuteMsg::Mint {
+                    token_id: "alice".to_owned(),
+                    owner: "owner".to_owned(),
+                    token_uri: None,
+                    extension: None,
+                },
+                mint_options: None,
+            },
+        );
+        let full_open_result = open_auction(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "silver",
+            silver(100),
+        );
+        mocked_env.block.time = end;
+        let settle_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info("anyone", &[]),
+            ExecuteMsg::SettleAuction { auction_id: 1 },
+        );
+
+        // Assert
+        assert!(open_response.events[0]
+            .attributes
+            .contains(&Attribute::new("open-auction-registration-price", "20")));
+        match mismatched_open_result {
+            Err(ContractError::YearlyPriceDenomMismatch { denom }) => assert_eq!(denom, "gold"),
+            _ => panic!(
+                "Expected YearlyPriceDenomMismatch, got {:?}",
+                mismatched_open_result
+            ),
+        }
+        match unregistered_bid_result {
+            Err(ContractError::BidTooLow { min_bid }) => assert_eq!(min_bid, silver(120)),
+            _ => panic!("Expected BidTooLow, got {:?}", unregistered_bid_result),
+        }
+        assert!(bid_result.is_ok(), "Failed to bid with the registration");
+        assert!(mint_result.is_ok(), "Failed to mint the last token");
+        match full_open_result {
+            Err(ContractError::MaxSupplyReached { max_supply }) => assert_eq!(max_supply, 1),
+            _ => panic!("Expected MaxSupplyReached, got {:?}", full_open_result),
+        }
+        assert_eq!(
+            settle_response.expect("Failed to settle auction"),
+            Response::default()
+                .add_message(BankMsg::Send {
+                    to_address: "alice".to_owned(),
+                    amount: vec![silver(120)],
+                })
+                .add_event(
+                    Event::new("my-collection-manager")
+                        .add_attribute("settle-auction", "1")
+                        .add_attribute("settle-auction-winner", "alice")
+                        .add_attribute("settle-auction-max-supply", "1")
+                        .add_attribute("settle-auction-refund", "120silver")
+                )
+        );
+    }
+
+    #[test]
+    fn test_auction_settlement_failures() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams::new(Addr::unchecked("beneficiary"), None),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        add_mock_collection(&mut mocked_deps_mut.storage, "unqueryable");
+        let end = mocked_env.block.time.plus_seconds(100);
+        for (auction_id, collection) in [(1, "collection"), (2, "unqueryable")] {
+            let _ = super::execute(
+                mocked_deps_mut.as_mut(),
+                mocked_env.to_owned(),
+                testing::mock_info(deployer.as_ref(), &[]),
+                ExecuteMsg::OpenAuction {
+                    collection: collection.to_owned(),
+                    token_id: "gold".to_owned(),
+                    reserve_price: silver(100),
+                    end,
+                    min_increment: Uint128::from(10u16),
+                },
+            )
+            .expect("Failed to open auction");
+            let _ = super::execute(
+                mocked_deps_mut.as_mut(),
+                mocked_env.to_owned(),
+                testing::mock_info("alice", &[silver(100)]),
+                ExecuteMsg::Bid { auction_id },
+            )
+            .expect("Failed to bid");
+        }
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::UpdatePaymentParams(PaymentParams {
+                yearly_price: Some(silver(20)),
+                ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+            }),
+        )
+        .expect("Failed to update payment params");
+        mocked_env.block.time = end;
+
+        // Act
+        let settle_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            ExecuteMsg::SettleAuction { auction_id: 1 },
+        );
+        let failed_settle_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            ExecuteMsg::SettleAuction { auction_id: 2 },
+        );
+
+        // Assert
+        assert!(settle_response.is_ok(), "Failed to settle auction");
+        assert!(!REGISTRATIONS.has(
+            &mocked_deps_mut.storage,
+            (&Addr::unchecked("collection"), "gold")
+        ));
+        let failed_settle_response =
+            failed_settle_response.expect("Failed to close the unsettleable auction");
+        assert_eq!(
+            failed_settle_response.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "alice".to_owned(),
+                amount: vec![silver(100)],
+            })]
+        );
+        let attributes = &failed_settle_response.events[0].attributes;
+        assert!(attributes
+            .iter()
+            .any(|attribute| attribute.key == "settle-auction-error"));
+        assert!(attributes.contains(&Attribute::new("settle-auction-refund", "100silver")));
+        assert!(!AUCTIONS.has(&mocked_deps_mut.storage, 2));
+        assert_eq!(
+            COLLECTION_MINT_COUNTS
+                .may_load(&mocked_deps_mut.storage, &Addr::unchecked("unqueryable"))
+                .expect("Failed to load mint count")
+                .unwrap_or_default(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_dutch_auction() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let start = mocked_env.block.time.plus_seconds(100);
+        let dutch_auction = DutchAuctionPrice {
+            start_price: silver(1000),
+            end_price: Uint128::from(200u16),
+            start,
+            duration: 800,
+            decay: PriceDecay::Linear,
+            refund_to_clearing_price: true,
+        };
+        let payment_params = PaymentParams {
+            dutch_auction: Some(dutch_auction.to_owned()),
+            ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: payment_params.to_owned(),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let mint_msg = |token_id: &str| ExecuteMsg::PassThrough {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: token_id.to_owned(),
+                owner: "owner".to_owned(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+        let settle_msg = |minter: &str| ExecuteMsg::SettleDutchEscrow {
+            collection: "collection".to_owned(),
+            minter: minter.to_owned(),
+        };
+        let price_query = |deps: Deps, env: Env| {
+            from_json::<CurrentMintPriceResponse>(
+                super::query(
+                    deps,
+                    env,
+                    QueryMsg::CurrentMintPrice {
+                        collection: "collection".to_owned(),
+                        token_id: None,
+                    },
+                )
+                .expect("Failed to query current mint price"),
+            )
+            .expect("Failed to deserialize current mint price")
+        };
+        let bank_msgs = |response: &Response| {
+            response
+                .messages
+                .iter()
+                .filter_map(|sub_msg| match &sub_msg.msg {
+                    CosmosMsg::Bank(bank_msg) => Some(bank_msg.to_owned()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let send = |to_address: &str, amount: u16| BankMsg::Send {
+            to_address: to_address.to_owned(),
+            amount: vec![silver(amount)],
+        };
+
+        // Act
+        let price_before_start = price_query(mocked_deps_mut.as_ref(), mocked_env.to_owned());
+        mocked_env.block.time = start.plus_seconds(400);
+        let alice_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("alice", &[silver(650)]),
+            mint_msg("first"),
+        );
+        mocked_env.block.time = start.plus_seconds(600);
+        let bob_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("bob", &[silver(400)]),
+            mint_msg("second"),
+        );
+        let price_after_mints = price_query(mocked_deps_mut.as_ref(), mocked_env.to_owned());
+        let early_settle_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            settle_msg("alice"),
+        );
+        mocked_env.block.time = dutch_auction.ends_at();
+        let alice_settle_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            settle_msg("alice"),
+        );
+        let bob_settle_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            settle_msg("bob"),
+        );
+        let repeated_settle_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("anyone", &[]),
+            settle_msg("bob"),
+        );
+
+        // Assert
+        assert_eq!(price_before_start.prices, vec![silver(1000)]);
+        assert_eq!(price_before_start.clearing_price, None);
+        assert_eq!(
+            DutchAuctionPrice {
+                decay: PriceDecay::Stepwise { step: 300 },
+                ..dutch_auction.to_owned()
+            }
+            .price_at(start.plus_seconds(400)),
+            silver(700)
+        );
+        assert!(matches!(
+            PaymentParams {
+                mint_price: Some(silver(10)),
+                ..payment_params
+            }
+            .validate(),
+            Err(ContractError::DutchAuctionWithOtherPrices)
+        ));
+        assert_eq!(
+            bank_msgs(&alice_response.expect("Failed to mint for alice")),
+            vec![send("beneficiary", 200), send("alice", 50)]
+        );
+        assert_eq!(
+            bank_msgs(&bob_response.expect("Failed to mint for bob")),
+            vec![send("beneficiary", 200)]
+        );
+        assert_eq!(price_after_mints.prices, vec![silver(400)]);
+        assert_eq!(price_after_mints.clearing_price, Some(silver(400)));
+        match early_settle_result {
+            Err(ContractError::DutchAuctionNotOver { ends_at }) => {
+                assert_eq!(ends_at, dutch_auction.ends_at())
+            }
+            _ => panic!(
+                "Expected DutchAuctionNotOver, got {:?}",
+                early_settle_result
+            ),
+        }
+        assert_eq!(
+            bank_msgs(&alice_settle_response.expect("Failed to settle alice")),
+            vec![send("beneficiary", 200), send("alice", 200)]
+        );
+        assert_eq!(
+            bank_msgs(&bob_settle_response.expect("Failed to settle bob")),
+            vec![send("beneficiary", 200)]
+        );
+        match repeated_settle_result {
+            Err(ContractError::NoDutchEscrow { minter }) => assert_eq!(minter, "bob"),
+            _ => panic!("Expected NoDutchEscrow, got {:?}", repeated_settle_result),
+        }
+    }
+
+    #[test]
+    fn test_dutch_auctions_in_sequence() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_env = testing::mock_env();
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let dutch_auction = |start: Timestamp| DutchAuctionPrice {
+            start_price: silver(1000),
+            end_price: Uint128::from(200u16),
+            start,
+            duration: 800,
+            decay: PriceDecay::Linear,
+            refund_to_clearing_price: true,
+        };
+        let payment_params = |dutch_auction: DutchAuctionPrice| PaymentParams {
+            dutch_auction: Some(dutch_auction),
+            ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+        };
+        let first_auction = dutch_auction(mocked_env.block.time);
+        let second_auction = dutch_auction(first_auction.ends_at().plus_seconds(100));
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: payment_params(first_auction.to_owned()),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let mint = |deps: DepsMut, env: Env, minter: &str, paid: u16| {
+            super::execute(
+                deps,
+                env,
+                testing::mock_info(minter, &[silver(paid)]),
+                ExecuteMsg::PassThrough {
+                    collection: "collection".to_owned(),
+                    message: CollectionExecuteMsg::Mint {
+                        token_id: minter.to_owned(),
+                        owner: "owner".to_owned(),
+                        token_uri: None,
+                        extension: None,
+                    },
+                    mint_options: None,
+                },
+            )
+        };
+        let settle = |deps: DepsMut, env: Env, minter: &str| {
+            super::execute(
+                deps,
+                env,
+                testing::mock_info("anyone", &[]),
+                ExecuteMsg::SettleDutchEscrow {
+                    collection: "collection".to_owned(),
+                    minter: minter.to_owned(),
+                },
+            )
+        };
+        mocked_env.block.time = first_auction.start.plus_seconds(600);
+        let _ = mint(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "alice",
+            400,
+        )
+        .expect("Failed to mint in the first auction");
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::UpdatePaymentParams(payment_params(second_auction.to_owned())),
+        )
+        .expect("Failed to replace the dutch auction");
+
+        // Act
+        mocked_env.block.time = second_auction.start.plus_seconds(200);
+        let _ = mint(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            "carol",
+            800,
+        )
+        .expect("Failed to mint in the second auction");
+        let price = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::CurrentMintPrice {
+                collection: "collection".to_owned(),
+                token_id: None,
+            },
+        );
+        let early_carol_settle_result =
+            settle(mocked_deps_mut.as_mut(), mocked_env.to_owned(), "carol");
+        let alice_settle_response =
+            settle(mocked_deps_mut.as_mut(), mocked_env.to_owned(), "alice");
+        mocked_env.block.time = second_auction.ends_at();
+        let carol_settle_response = settle(mocked_deps_mut.as_mut(), mocked_env, "carol");
+
+        // Assert
+        assert_eq!(
+            from_json::<CurrentMintPriceResponse>(price.expect("Failed to query mint price"))
+                .expect("Failed to deserialize mint price")
+                .clearing_price,
+            Some(silver(800))
+        );
+        match early_carol_settle_result {
+            Err(ContractError::DutchAuctionNotOver { ends_at }) => {
+                assert_eq!(ends_at, second_auction.ends_at())
+            }
+            _ => panic!(
+                "Expected DutchAuctionNotOver, got {:?}",
+                early_carol_settle_result
+            ),
+        }
+        assert_eq!(
+            alice_settle_response
+                .expect("Failed to settle the first auction")
+                .messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "beneficiary".to_owned(),
+                amount: vec![silver(200)],
+            })]
+        );
+        assert_eq!(
+            carol_settle_response
+                .expect("Failed to settle the second auction")
+                .messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "beneficiary".to_owned(),
+                amount: vec![silver(600)],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_bonding_curve() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let bonding_curve = BondingCurve {
+            base_price: silver(100),
+            shape: CurveShape::Linear {
+                slope: Uint128::from(10u16),
+            },
+        };
+        let payment_params = PaymentParams {
+            bonding_curve: Some(bonding_curve.to_owned()),
+            ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: payment_params.to_owned(),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let mint_item = |token_id: &str| PassThroughItem {
+            collection: "collection".to_owned(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: token_id.to_owned(),
+                owner: "owner".to_owned(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        };
+        let payout_msg = |amount: u16| {
+            SubMsg::new(BankMsg::Send {
+                to_address: "beneficiary".to_owned(),
+                amount: vec![silver(amount)],
+            })
+        };
+
+        // Act
+        let next_prices = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::NextMintPrices {
+                collection: "collection".to_owned(),
+                count: 3,
+            },
+        );
+        let underpaid_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[silver(100)]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: mint_item("alice").message,
+                mint_options: None,
+            },
+        );
+        let batch_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[silver(270)]),
+            ExecuteMsg::BatchPassThrough {
+                items: vec![mint_item("alice"), mint_item("bob")],
+                mode: BatchMode::Atomic,
+            },
+        );
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::UpdatePaymentParams(PaymentParams {
+                bonding_curve: Some(BondingCurve {
+                    base_price: Coin::new(u128::MAX / 1_000, "silver"),
+                    shape: CurveShape::Exponential {
+                        growth_bps: u32::MAX,
+                    },
+                }),
+                ..payment_params.to_owned()
+            }),
+        )
+        .expect("Failed to update payment params");
+        let overflowing_prices = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::NextMintPrices {
+                collection: "collection".to_owned(),
+                count: 3,
+            },
+        );
+        let overflowing_quote = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::SimulateMint {
+                collection: "collection".to_owned(),
+                sender: "executer".to_owned(),
+                funds: vec![silver(100)],
+                token_id: None,
+                years: None,
+            },
+        );
+
+        // Assert
+        let next_prices = from_json::<NextMintPricesResponse>(
+            next_prices.expect("Failed to query next mint prices"),
+        )
+        .expect("Failed to deserialize next mint prices");
+        assert_eq!(next_prices.supply, 3);
+        assert_eq!(
+            next_prices.prices,
+            vec![silver(130), silver(140), silver(150)]
+        );
+        assert_eq!(next_prices.total, Some(silver(420)));
+        match underpaid_result {
+            Err(ContractError::MissingPayment { accepted_payments }) => {
+                assert_eq!(accepted_payments, vec![silver(130)])
+            }
+            _ => panic!("Expected MissingPayment, got {:?}", underpaid_result),
+        }
+        let batch_response = batch_response.expect("Failed to mint batch");
+        assert_eq!(batch_response.messages[0], payout_msg(130));
+        assert_eq!(batch_response.messages[2], payout_msg(140));
+        assert_eq!(batch_response.messages.len(), 4);
+        assert!(
+            overflowing_prices.is_err(),
+            "Expected overflowing next mint prices to fail"
+        );
+        let overflowing_quote =
+            from_json::<SimulateMintResponse>(overflowing_quote.expect("Failed to simulate mint"))
+                .expect("Failed to deserialize simulation");
+        assert_eq!(overflowing_quote.payment, None);
+        assert!(overflowing_quote.error.is_some());
+        let exponential = BondingCurve {
+            shape: CurveShape::Exponential { growth_bps: 1_000 },
+            ..bonding_curve.to_owned()
+        };
+        assert_eq!(
+            exponential.price_at(2).expect("Failed to price"),
+            silver(121)
+        );
+        let overflowing = BondingCurve {
+            base_price: Coin::new(u128::MAX, "silver"),
+            ..exponential.to_owned()
+        };
+        assert!(overflowing.price_at(2).is_err());
+        let steps = BondingCurve {
+            shape: CurveShape::Steps {
+                steps: vec![
+                    SupplyStep {
+                        min_supply: 5,
+                        price: Uint128::from(300u16),
+                    },
+                    SupplyStep {
+                        min_supply: 2,
+                        price: Uint128::from(150u16),
+                    },
+                ],
+            },
+            ..bonding_curve.to_owned()
+        };
+        assert_eq!(steps.price_at(1).expect("Failed to price"), silver(100));
+        assert_eq!(steps.price_at(4).expect("Failed to price"), silver(150));
+        assert_eq!(steps.price_at(5).expect("Failed to price"), silver(300));
+        assert!(matches!(
+            PaymentParams {
+                mint_price: Some(silver(10)),
+                ..payment_params
+            }
+            .validate(),
+            Err(ContractError::BondingCurveWithOtherPrices)
+        ));
+    }
+
+    #[test]
+    fn test_accrue_revenue() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams::new(
+                    Addr::unchecked("beneficiary"),
+                    Some(silver(10)),
+                ),
+                config: Config {
+                    accrue_revenue: true,
+                    ..Config::default()
+                },
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let withdraw = |deps: DepsMut, amount: Option<u16>| {
+            super::execute(
+                deps,
+                testing::mock_env(),
+                testing::mock_info("beneficiary", &[]),
+                ExecuteMsg::Withdraw {
+                    denoms: vec![],
+                    amount: amount.map(Uint128::from),
+                },
+            )
+        };
+
+        // Act
+        let mint_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("executer", &[silver(10)]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: CollectionExecuteMsg::Mint {
+                    token_id: "alice".to_owned(),
+                    owner: "owner".to_owned(),
+                    token_uri: None,
+                    extension: None,
+                },
+                mint_options: None,
+            },
+        );
+        let claimable = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::Claimable {
+                address: "beneficiary".to_owned(),
+            },
+        );
+        let partial_withdraw_response = withdraw(mocked_deps_mut.as_mut(), Some(4));
+        let excessive_withdraw_result = withdraw(mocked_deps_mut.as_mut(), Some(7));
+        let full_withdraw_response = withdraw(mocked_deps_mut.as_mut(), None);
+        let empty_withdraw_result = withdraw(mocked_deps_mut.as_mut(), None);
+        let revenue = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::CollectionRevenue {
+                collection: "collection".to_owned(),
+            },
+        );
+
+        // Assert
+        let mint_response = mint_response.expect("Failed to mint");
+        assert_eq!(mint_response.messages.len(), 1);
+        assert!(matches!(mint_response.messages[0].msg, CosmosMsg::Wasm(_)));
+        assert_eq!(
+            from_json::<ClaimableResponse>(claimable.expect("Failed to query claimable"))
+                .expect("Failed to deserialize claimable")
+                .balances,
+            vec![silver(10)]
+        );
+        let send_to_beneficiary = |amount: u16| {
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "beneficiary".to_owned(),
+                amount: vec![silver(amount)],
+            })]
+        };
+        assert_eq!(
+            partial_withdraw_response
+                .expect("Failed to withdraw part")
+                .messages,
+            send_to_beneficiary(4)
+        );
+        match excessive_withdraw_result {
+            Err(ContractError::InsufficientClaimable { denom, claimable }) => {
+                assert_eq!(denom, "silver");
+                assert_eq!(claimable, Uint128::from(6u16));
+            }
+            _ => panic!(
+                "Expected InsufficientClaimable, got {:?}",
+                excessive_withdraw_result
+            ),
+        }
+        assert_eq!(
+            full_withdraw_response
+                .expect("Failed to withdraw the rest")
+                .messages,
+            send_to_beneficiary(6)
+        );
+        assert!(matches!(
+            empty_withdraw_result,
+            Err(ContractError::NothingToWithdraw)
+        ));
+        assert_eq!(
+            from_json::<CollectionRevenueResponse>(revenue.expect("Failed to query revenue"))
+                .expect("Failed to deserialize revenue")
+                .revenue,
+            vec![silver(10)]
+        );
+    }
+
+    #[test]
+    fn test_pause() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams::new(Addr::unchecked("beneficiary"), None),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::SetGuardian {
+                guardian: Some("guardian".to_owned()),
+            },
+        )
+        .expect("Failed to set guardian");
+        let pass_through = |deps: DepsMut, message: CollectionExecuteMsg| {
+            super::execute(
+                deps,
+                testing::mock_env(),
+                testing::mock_info("executer", &[]),
+                ExecuteMsg::PassThrough {
+                    collection: "collection".to_owned(),
+                    message,
+                    mint_options: None,
+                },
+            )
+        };
+        let mint_msg = CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        };
+        let burn_msg = CollectionExecuteMsg::Burn {
+            token_id: "alice".to_owned(),
+        };
+
+        // Act
+        let stranger_pause_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("stranger", &[]),
+            ExecuteMsg::Pause {
+                collection: None,
+                allowed_actions: vec![],
+            },
+        );
+        let pause_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("guardian", &[]),
+            ExecuteMsg::Pause {
+                collection: Some("collection".to_owned()),
+                allowed_actions: vec![CollectionAction::Burn],
+            },
+        );
+        let paused_mint_result = pass_through(mocked_deps_mut.as_mut(), mint_msg.to_owned());
+        let allowed_burn_result = pass_through(mocked_deps_mut.as_mut(), burn_msg.to_owned());
+        let _ = super::sudo(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            SudoMsg::Pause {
+                collection: None,
+                allowed_actions: vec![],
+            },
+        )
+        .expect("Failed to pause all collections");
+        let pause_status = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::PauseStatus {
+                collection: Some("collection".to_owned()),
+            },
+        );
+        let globally_paused_burn_result = pass_through(mocked_deps_mut.as_mut(), burn_msg);
+        for collection in [None, Some("collection".to_owned())] {
+            let _ = super::execute(
+                mocked_deps_mut.as_mut(),
+                mocked_env.to_owned(),
+                testing::mock_info("guardian", &[]),
+                ExecuteMsg::Unpause { collection },
+            )
+            .expect("Failed to unpause");
+        }
+        let unpaused_mint_result = pass_through(mocked_deps_mut.as_mut(), mint_msg);
+
+        // Assert
+        assert!(matches!(
+            stranger_pause_result,
+            Err(ContractError::Unauthorized)
+        ));
+        assert_eq!(
+            pause_response.expect("Failed to pause collection").events,
+            vec![Event::new("my-collection-manager")
+                .add_attribute("pause", "collection")
+                .add_attribute("pause-allowed-actions", "burn")]
+        );
+        match paused_mint_result {
+            Err(ContractError::Paused { collection }) => assert_eq!(collection, "collection"),
+            _ => panic!("Expected Paused, got {:?}", paused_mint_result),
+        }
+        assert!(allowed_burn_result.is_ok(), "Failed to burn while paused");
+        assert_eq!(
+            from_json::<PauseStatusResponse>(pause_status.expect("Failed to query pause status"))
+                .expect("Failed to deserialize pause status"),
+            PauseStatusResponse {
+                global: Some(PauseState {
+                    allowed_actions: vec![],
+                }),
+                collection: Some(PauseState {
+                    allowed_actions: vec![CollectionAction::Burn],
+                }),
+                guardian: Some(Addr::unchecked("guardian")),
+            }
+        );
+        assert!(matches!(
+            globally_paused_burn_result,
+            Err(ContractError::Paused { .. })
+        ));
+        assert!(
+            unpaused_mint_result.is_ok(),
+            "Failed to mint after unpausing"
+        );
+    }
+
+    #[test]
+    fn test_pass_through_policy() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: PaymentParams::new(Addr::unchecked("beneficiary"), None),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let pass_through = |deps: DepsMut, sender: &str, message: CollectionExecuteMsg| {
+            super::execute(
+                deps,
+                testing::mock_env(),
+                testing::mock_info(sender, &[]),
+                ExecuteMsg::PassThrough {
+                    collection: "collection".to_owned(),
+                    message,
+                    mint_options: None,
+                },
+            )
+        };
+        let withdraw_msg = CollectionExecuteMsg::WithdrawFunds {
+            amount: Coin {
+                denom: "silver".to_owned(),
+                amount: Uint128::from(10u16),
+            },
+        };
+        let set_policy = |deps: DepsMut, rules: Vec<ActionRule>| {
+            super::execute(
+                deps,
+                testing::mock_env(),
+                testing::mock_info("deployer", &[]),
+                ExecuteMsg::SetPassThroughPolicy {
+                    collection: "collection".to_owned(),
+                    policy: PassThroughPolicy { rules },
+                },
+            )
+        };
+        let transfer_rule = ActionRule {
+            action: CollectionAction::TransferNft,
+            access: ActionAccess::Forbidden,
+        };
+        let update_info_rule = ActionRule {
+            action: CollectionAction::UpdateNftInfo,
+            access: ActionAccess::Public,
+        };
+
+        // Act
+        let stranger_withdraw_result = pass_through(
+            mocked_deps_mut.as_mut(),
+            "stranger",
+            withdraw_msg.to_owned(),
+        );
+        let owner_withdraw_result =
+            pass_through(mocked_deps_mut.as_mut(), "deployer", withdraw_msg);
+        let duplicate_rule_result = set_policy(
+            mocked_deps_mut.as_mut(),
+            vec![transfer_rule.to_owned(), transfer_rule.to_owned()],
+        );
+        let _ = set_policy(
+            mocked_deps_mut.as_mut(),
+            vec![transfer_rule.to_owned(), update_info_rule.to_owned()],
+        )
+        .expect("Failed to set pass-through policy");
+        let forbidden_transfer_result = pass_through(
+            mocked_deps_mut.as_mut(),
+            "deployer",
+            CollectionExecuteMsg::TransferNft {
+                recipient: "recipient".to_owned(),
+                token_id: "alice".to_owned(),
+            },
+        );
+        let update_info_msg = CollectionExecuteMsg::UpdateNftInfo {
+            token_id: "alice".to_owned(),
+            token_uri: None,
+            extension: None,
+        };
+        let stranger_update_result = pass_through(
+            mocked_deps_mut.as_mut(),
+            "stranger",
+            update_info_msg.to_owned(),
+        );
+        let token_owner_update_result =
+            pass_through(mocked_deps_mut.as_mut(), "executer", update_info_msg);
+        let policy = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env,
+            QueryMsg::PassThroughPolicy {
+                collection: "collection".to_owned(),
+            },
+        );
+
+        // Assert
+        match stranger_withdraw_result {
+            Err(ContractError::OwnerOnlyAction { action }) => assert_eq!(action, "withdraw_funds"),
+            _ => panic!(
+                "Expected OwnerOnlyAction, got {:?}",
+                stranger_withdraw_result
+            ),
+        }
+        assert!(owner_withdraw_result.is_ok(), "Failed to withdraw as owner");
+        match duplicate_rule_result {
+            Err(ContractError::DuplicateActionRule { action }) => {
+                assert_eq!(action, "transfer_nft")
+            }
+            _ => panic!(
+                "Expected DuplicateActionRule, got {:?}",
+                duplicate_rule_result
+            ),
+        }
+        match forbidden_transfer_result {
+            Err(ContractError::ForbiddenAction { action, collection }) => {
+                assert_eq!(action, "transfer_nft");
+                assert_eq!(collection, "collection");
+            }
+            _ => panic!(
+                "Expected ForbiddenAction, got {:?}",
+                forbidden_transfer_result
+            ),
+        }
+        match stranger_update_result {
+            Err(ContractError::NotTokenOwner { sender, token_id }) => {
+                assert_eq!(sender, "stranger");
+                assert_eq!(token_id, "alice");
+            }
+            _ => panic!("Expected NotTokenOwner, got {:?}", stranger_update_result),
+        }
+        assert!(
+            token_owner_update_result.is_ok(),
+            "Failed to update nft info as token owner"
+        );
+        let policy = from_json::<PassThroughPolicyResponse>(
+            policy.expect("Failed to query pass-through policy"),
+        )
+        .expect("Failed to deserialize pass-through policy");
+        assert_eq!(policy.rules.len(), CollectionAction::ALL.len());
+        assert!(policy.rules.contains(&transfer_rule));
+        assert!(policy.rules.contains(&update_info_rule));
+        assert!(policy.rules.contains(&ActionRule {
+            action: CollectionAction::Approve,
+            access: ActionAccess::Public,
+        }));
+        assert!(policy.rules.contains(&ActionRule {
+            action: CollectionAction::ApproveAll,
+            access: ActionAccess::OwnerOnly,
+        }));
+        assert!(policy.rules.contains(&ActionRule {
+            action: CollectionAction::UpdateCollectionInfo,
+            access: ActionAccess::OwnerOnly,
+        }));
+    }
+
+    #[test]
+    fn test_action_fees() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let silver = |amount: u16| Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(amount),
+        };
+        let payment_params = PaymentParams {
+            action_fees: vec![
+                ActionFee {
+                    action: CollectionAction::TransferNft,
+                    price: silver(4),
+                    revenue_split: vec![
+                        RevenueShare {
+                            recipient: Addr::unchecked("artist"),
+                            weight: 3,
+                        },
+                        RevenueShare {
+                            recipient: Addr::unchecked("marketplace"),
+                            weight: 1,
+                        },
+                    ],
+                    rebate: false,
+                },
+                ActionFee {
+                    action: CollectionAction::Burn,
+                    price: silver(3),
+                    revenue_split: vec![],
+                    rebate: true,
+                },
+            ],
+            ..PaymentParams::new(Addr::unchecked("beneficiary"), Some(silver(10)))
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: None,
+                payment_params: payment_params.to_owned(),
+                config: Config::default(),
+            },
+        )
+        .expect("Failed to instantiate manager");
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let transfer_msg = CollectionExecuteMsg::TransferNft {
+            recipient: "recipient".to_owned(),
+            token_id: "alice".to_owned(),
+        };
+        let transfer = |deps: DepsMut, funds: &[Coin]| {
+            super::execute(
+                deps,
+                testing::mock_env(),
+                testing::mock_info("executer", funds),
+                ExecuteMsg::PassThrough {
+                    collection: "collection".to_owned(),
+                    message: transfer_msg.to_owned(),
+                    mint_options: None,
+                },
+            )
+        };
+        let expected_payouts = vec![
+            MintPayout {
+                recipient: Addr::unchecked("artist"),
+                amount: silver(3),
+            },
+            MintPayout {
+                recipient: Addr::unchecked("marketplace"),
+                amount: silver(1),
+            },
+        ];
+
+        // Act
+        let underpaid_result = transfer(mocked_deps_mut.as_mut(), &[silver(3)]);
+        let transfer_response = transfer(mocked_deps_mut.as_mut(), &[silver(6)]);
+        let quote = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::SimulateAction {
+                collection: "collection".to_owned(),
+                sender: "executer".to_owned(),
+                funds: vec![silver(6)],
+                action: CollectionAction::TransferNft,
+            },
+        );
+        let burn = |deps: DepsMut, funds: &[Coin]| {
+            super::execute(
+                deps,
+                testing::mock_env(),
+                testing::mock_info("executer", funds),
+                ExecuteMsg::PassThrough {
+                    collection: "collection".to_owned(),
+                    message: CollectionExecuteMsg::Burn {
+                        token_id: "alice".to_owned(),
+                    },
+                    mint_options: None,
+                },
+            )
+        };
+        let rebate_pool = |deps: Deps| {
+            from_json::<RebatePoolResponse>(
+                super::query(
+                    deps,
+                    testing::mock_env(),
+                    QueryMsg::RebatePool {
+                        collection: "collection".to_owned(),
+                    },
+                )
+                .expect("Failed to query rebate pool"),
+            )
+            .expect("Failed to deserialize rebate pool")
+            .funds
+        };
+        let unfunded_burn_result = burn(mocked_deps_mut.as_mut(), &[]);
+        let stranger_fund_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("stranger", &[silver(5)]),
+            ExecuteMsg::FundRebatePool {
+                collection: "collection".to_owned(),
+            },
+        );
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[silver(5)]),
+            ExecuteMsg::FundRebatePool {
+                collection: "collection".to_owned(),
+            },
+        )
+        .expect("Failed to fund rebate pool");
+        let rebate_quote = super::query(
+            mocked_deps_mut.as_ref(),
+            mocked_env.to_owned(),
+            QueryMsg::SimulateAction {
+                collection: "collection".to_owned(),
+                sender: "executer".to_owned(),
+                funds: vec![],
+                action: CollectionAction::Burn,
+            },
+        );
+        let burn_response = burn(mocked_deps_mut.as_mut(), &[silver(2)]);
+        let drawn_pool = rebate_pool(mocked_deps_mut.as_ref());
+        let drained_burn_result = burn(mocked_deps_mut.as_mut(), &[]);
+        let withdraw_pool_response = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::WithdrawRebatePool {
+                collection: "collection".to_owned(),
+                amount: silver(2),
+            },
+        );
+        let withdrawn_pool = rebate_pool(mocked_deps_mut.as_ref());
+        let stranger_burn_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("stranger", &[]),
+            ExecuteMsg::PassThrough {
+                collection: "collection".to_owned(),
+                message: CollectionExecuteMsg::Burn {
+                    token_id: "alice".to_owned(),
+                },
+                mint_options: None,
+            },
+        );
+        let mint_fee_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::UpdatePaymentParams(PaymentParams {
+                action_fees: vec![ActionFee {
+                    action: CollectionAction::Mint,
+                    price: silver(1),
+                    revenue_split: vec![],
+                    rebate: false,
+                }],
+                ..payment_params.to_owned()
+            }),
+        );
+        let transfer_rebate_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("deployer", &[]),
+            ExecuteMsg::UpdatePaymentParams(PaymentParams {
+                action_fees: vec![ActionFee {
+                    action: CollectionAction::TransferNft,
+                    price: silver(1),
+                    revenue_split: vec![],
+                    rebate: true,
+                }],
+                ..payment_params
+            }),
+        );
+
+        // Assert
+        assert!(
+            underpaid_result.is_err(),
+            "Expected an underpaid transfer to fail"
+        );
+        let transfer_response = transfer_response.expect("Failed to transfer");
+        assert_eq!(
+            transfer_response.messages[..3]
+                .iter()
+                .map(|sub_msg| sub_msg.msg.to_owned())
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "artist".to_owned(),
+                    amount: vec![silver(3)],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "marketplace".to_owned(),
+                    amount: vec![silver(1)],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "executer".to_owned(),
+                    amount: vec![silver(2)],
+                }),
+            ]
+        );
+        assert!(transfer_response
+            .events
+            .contains(&Event::new("my-collection-manager").add_attribute("action-fee", "4silver")));
+        assert_eq!(
+            from_json::<SimulateMintResponse>(quote.expect("Failed to query quote"))
+                .expect("Failed to deserialize quote"),
+            SimulateMintResponse {
+                payment: Some(silver(4)),
+                payouts: expected_payouts,
+                refund: vec![silver(2)],
+                rebate: vec![],
+                error: None,
+            }
+        );
+        match unfunded_burn_result {
+            Err(ContractError::RebatePoolTooLow { collection, amount }) => {
+                assert_eq!(collection, "collection");
+                assert_eq!(amount, silver(3));
+            }
+            _ => panic!("Expected RebatePoolTooLow, got {:?}", unfunded_burn_result),
+        }
+        assert!(matches!(
+            stranger_fund_result,
+            Err(ContractError::Unauthorized)
+        ));
+        let burn_response = burn_response.expect("Failed to burn");
+        assert_eq!(
+            burn_response.messages[..2]
+                .iter()
+                .map(|sub_msg| sub_msg.msg.to_owned())
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "executer".to_owned(),
+                    amount: vec![silver(3)],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "executer".to_owned(),
+                    amount: vec![silver(2)],
+                }),
+            ]
+        );
+        assert!(burn_response.events.contains(
+            &Event::new("my-collection-manager").add_attribute("action-rebate", "3silver")
+        ));
+        assert_eq!(
+            from_json::<SimulateMintResponse>(rebate_quote.expect("Failed to query rebate quote"))
+                .expect("Failed to deserialize rebate quote"),
+            SimulateMintResponse {
+                payment: None,
+                payouts: vec![],
+                refund: vec![],
+                rebate: vec![MintPayout {
+                    recipient: Addr::unchecked("executer"),
+                    amount: silver(3),
+                }],
+                error: None,
+            }
+        );
+        assert_eq!(drawn_pool, vec![silver(2)]);
+        assert!(matches!(
+            drained_burn_result,
+            Err(ContractError::RebatePoolTooLow { .. })
+        ));
+        assert_eq!(
+            withdraw_pool_response
+                .expect("Failed to withdraw rebate pool")
+                .messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "deployer".to_owned(),
+                amount: vec![silver(2)],
+            })]
+        );
+        assert_eq!(withdrawn_pool, vec![]);
+        match stranger_burn_result {
+            Err(ContractError::NotTokenOwner { sender, token_id }) => {
+                assert_eq!(sender, "stranger");
+                assert_eq!(token_id, "alice");
+            }
+            _ => panic!("Expected NotTokenOwner, got {:?}", stranger_burn_result),
+        }
+        assert!(matches!(
+            mint_fee_result,
+            Err(ContractError::ActionFeeForMint)
+        ));
+        match transfer_rebate_result {
+            Err(ContractError::RebateNotBurn { action }) => assert_eq!(action, "transfer_nft"),
+            _ => panic!("Expected RebateNotBurn, got {:?}", transfer_rebate_result),
+        }
+    }
+
+    #[test]
+    fn test_reply_pass_through() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let num_tokens = to_json_binary(&NameServiceExecuteMsgResponse { num_tokens: 4 })
+            .expect("Failed to serialize counter");
+        let reply = Reply {
+            id: save_pass_through_context(&mut mocked_deps_mut.storage),
+            result: SubMsgResult::Ok(SubMsgResponse {
+                data: Some(encode_execute_response_data(num_tokens.as_slice())),
+                events: vec![],
+            }),
+        };
+
+        // Act
+        let contract_result = super::reply(mocked_deps_mut.as_mut(), mocked_env, reply);
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass reply through");
+        let received_response = contract_result.unwrap();
+        let expected_response = Response::default()
+            .add_event(Event::new("my-collection-manager").add_attribute("token-count-after", "4"));
+        assert_eq!(received_response, expected_response);
+    }
+
+    #[test]
+    fn test_reply_unknown_id() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let known_id = save_pass_through_context(&mut mocked_deps_mut.storage);
+        let reply_with = |id: u64| Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                data: None,
+                events: vec![],
+            }),
+        };
+
+        // Act
+        let unknown_code_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            reply_with(super::reply_id(1, ReplyCode::PassThrough) + 9),
+        );
+        let missing_context_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            reply_with(super::reply_id(2, ReplyCode::PassThrough)),
+        );
+        let mismatched_code_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            reply_with(super::reply_id(1, ReplyCode::BatchItem)),
+        );
+        let known_result = super::reply(mocked_deps_mut.as_mut(), mocked_env, reply_with(known_id));
+
+        // Assert
+        for (contract_result, expected_id) in [
+            (unknown_code_result, 266),
+            (missing_context_result, 513),
+            (mismatched_code_result, 259),
+        ] {
+            match contract_result {
+                Err(ContractError::UnknownReplyId { id }) => assert_eq!(id, expected_id),
+                _ => panic!("Expected UnknownReplyId, got {:?}", contract_result),
             }
         }
+        assert_eq!(known_id, 257);
+        assert!(known_result.is_ok(), "Failed to pass reply through");
+    }
 
-        pub fn new(base: MockQuerier<Empty>, response: NumTokensResponse) -> Self {
-            NumTokensMockQuerier { base, response }
+    #[test]
+    fn test_reply_pass_through_large_payload() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let padded_num_tokens = format!("{{\"num_tokens\":{}4}}", " ".repeat(200));
+        let reply = Reply {
+            id: save_pass_through_context(&mut mocked_deps_mut.storage),
+            result: SubMsgResult::Ok(SubMsgResponse {
+                data: Some(encode_execute_response_data(padded_num_tokens.as_bytes())),
+                events: vec![],
+            }),
+        };
+
+        // Act
+        let contract_result = super::reply(mocked_deps_mut.as_mut(), mocked_env, reply);
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to pass reply through");
+        let expected_response = Response::default()
+            .add_event(Event::new("my-collection-manager").add_attribute("token-count-after", "4"));
+        assert_eq!(contract_result.unwrap(), expected_response);
+    }
+
+    #[test]
+    fn test_reply_pass_through_empty_payload() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mocked_env = testing::mock_env();
+        let reply_with = |id: u64, data: Option<Binary>| Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                data,
+                events: vec![],
+            }),
+        };
+        let no_data_id = save_pass_through_context(&mut mocked_deps_mut.storage);
+        let empty_data_id = save_pass_through_context(&mut mocked_deps_mut.storage);
+        let empty_inner_data_id = save_pass_through_context(&mut mocked_deps_mut.storage);
+
+        // Act
+        let no_data_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            reply_with(no_data_id, None),
+        );
+        let empty_data_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            reply_with(empty_data_id, Some(Binary::default())),
+        );
+        let empty_inner_data_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            reply_with(empty_inner_data_id, Some(encode_execute_response_data(&[]))),
+        );
+
+        // Assert
+        for contract_result in [no_data_result, empty_data_result, empty_inner_data_result] {
+            assert_eq!(
+                contract_result.expect("Failed to pass reply through"),
+                Response::default()
+            );
         }
     }
 
     #[test]
-    fn test_instantiate() {
+    fn test_reply_pass_through_unexpected_payload() {
         // Arrange
         let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
         let mocked_env = testing::mock_env();
+        let reply_with = |id: u64, data: Binary| Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                data: Some(data),
+                events: vec![],
+            }),
+        };
+        let other_data = b"not a token count".to_vec();
+        let other_data_id = save_pass_through_context(&mut mocked_deps_mut.storage);
+        let truncated_id = save_pass_through_context(&mut mocked_deps_mut.storage);
+
+        // Act
+        let other_data_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            reply_with(other_data_id, encode_execute_response_data(&other_data)),
+        );
+        let truncated_result = super::reply(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            reply_with(truncated_id, Binary::from(vec![10, 200])),
+        );
+
+        // Assert
+        let expected_response = Response::default().add_event(
+            Event::new("my-collection-manager")
+                .add_attribute("unparsed-data", Binary::from(other_data).to_base64()),
+        );
+        assert_eq!(
+            other_data_result.expect("Failed to pass reply through"),
+            expected_response
+        );
+        match truncated_result {
+            Err(ContractError::ParseReply(_)) => {}
+            _ => panic!("Expected ParseReply, got {:?}", truncated_result),
+        }
+    }
+
+    #[test]
+    fn test_create_collection() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 0 });
+        let mocked_env = testing::mock_env();
         let deployer = Addr::unchecked("deployer");
         let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
-        let payment_params = PaymentParams {
-            beneficiary: deployer.to_owned(),
-            mint_price: None,
-        };
         let instantiate_msg = InstantiateMsg {
-            payment_params: payment_params.to_owned(),
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config {
+                collection_code_id: Some(MOCK_CODE_ID),
+                max_batch_size: None,
+                mint_failure_policy: MintFailurePolicy::Revert,
+                accrue_revenue: false,
+                commit_reveal: None,
+            },
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            mocked_msg_info,
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
+        let execute_msg = ExecuteMsg::CreateCollection {
+            name: "my names".to_owned(),
+            symbol: "MYN".to_owned(),
+            label: "nameservice".to_owned(),
         };
 
         // Act
-        let result = super::instantiate(
+        let contract_result = super::execute(
             mocked_deps_mut.as_mut(),
             mocked_env.to_owned(),
             mocked_msg_info,
-            instantiate_msg.to_owned(),
+            execute_msg,
         );
 
         // Assert
-        assert!(result.is_ok(), "Failed to instantiate manager");
-        let received_response = result.unwrap();
+        assert!(contract_result.is_ok(), "Failed to create collection");
+        let received_response = contract_result.unwrap();
+        let expected_instantiate_msg = CollectionInstantiateMsg {
+            name: "my names".to_owned(),
+            symbol: "MYN".to_owned(),
+            collection_info_extension: None,
+            minter: Some(mocked_env.contract.address.to_string()),
+            creator: None,
+            withdraw_address: None,
+        };
+        let expected_response = Response::default().add_submessage(SubMsg {
+            id: super::reply_id(1, ReplyCode::CreateCollection),
+            msg: CosmosMsg::<Empty>::Wasm(WasmMsg::Instantiate {
+                admin: Some(mocked_env.contract.address.to_string()),
+                code_id: MOCK_CODE_ID,
+                msg: to_json_binary(&expected_instantiate_msg)
+                    .expect("Failed to serialize instantiate message"),
+                funds: vec![],
+                label: "nameservice".to_owned(),
+            }),
+            reply_on: ReplyOn::Success,
+            gas_limit: None,
+        });
+        assert_eq!(received_response, expected_response);
+        let reply_context = REPLY_CONTEXTS
+            .load(
+                &mocked_deps_mut.storage,
+                super::reply_id(1, ReplyCode::CreateCollection),
+            )
+            .expect("Failed to load reply context");
+        assert_eq!(
+            reply_context,
+            ReplyContext::CreateCollection(CollectionInfo {
+                label: "nameservice".to_owned(),
+                code_id: MOCK_CODE_ID,
+                created_at: mocked_env.block.time,
+            })
+        );
+    }
+
+    #[test]
+    fn test_reply_create_collection() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 0 });
+        let mocked_env = testing::mock_env();
+        let collection_info = CollectionInfo {
+            label: "nameservice".to_owned(),
+            code_id: MOCK_CODE_ID,
+            created_at: mocked_env.block.time,
+        };
+        let reply_id = super::save_reply_context(
+            &mut mocked_deps_mut.storage,
+            ReplyCode::CreateCollection,
+            &ReplyContext::CreateCollection(collection_info.to_owned()),
+        )
+        .expect("Failed to save reply context");
+        let collection = Addr::unchecked("new-collection");
+        let mut instantiate_data = vec![10, collection.as_str().len() as u8];
+        instantiate_data.extend_from_slice(collection.as_bytes());
+        let reply = Reply {
+            id: reply_id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                data: Some(Binary::from(instantiate_data)),
+                events: vec![],
+            }),
+        };
+
+        // Act
+        let contract_result = super::reply(mocked_deps_mut.as_mut(), mocked_env, reply);
+
+        // Assert
+        assert!(
+            contract_result.is_ok(),
+            "Failed to handle create collection reply"
+        );
+        let received_response = contract_result.unwrap();
         let expected_response = Response::default().add_event(
             Event::new("my-collection-manager")
-                .add_attribute("update-contract-version", "0.1.0")
-                .add_attribute("update-payment-params-beneficiary", deployer)
-                .add_attribute("update-payment-params-mint-price", "none"),
+                .add_attribute("create-collection", collection.to_owned())
+                .add_attribute("create-collection-label", "nameservice")
+                .add_attribute("create-collection-code-id", MOCK_CODE_ID.to_string()),
         );
         assert_eq!(received_response, expected_response);
-        let saved_payment_params = PAYMENT_PARAMS
+        assert_eq!(
+            COLLECTIONS
+                .load(&mocked_deps_mut.storage, &collection)
+                .expect("Failed to load collection"),
+            collection_info
+        );
+        assert!(REPLY_CONTEXTS.is_empty(&mocked_deps_mut.storage));
+    }
+
+    #[test]
+    fn test_update_payment_params() {
+        // Arrange
+        let mut mocked_deps_mut = testing::mock_dependencies();
+        let mocked_env = testing::mock_env();
+        let deployer = Addr::unchecked("deployer");
+        let instantiate_msg = InstantiateMsg {
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config::default(),
+        };
+        let _ = super::instantiate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            instantiate_msg,
+        )
+        .expect("Failed to instantiate manager");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let new_payment_params = PaymentParams::new(
+            beneficiary.to_owned(),
+            Some(Coin {
+                denom: "silver".to_owned(),
+                amount: Uint128::one(),
+            }),
+        );
+        let execute_msg = ExecuteMsg::UpdatePaymentParams(new_payment_params.to_owned());
+        let unauthorized_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("intruder", &[]),
+            execute_msg.to_owned(),
+        );
+        assert!(
+            matches!(unauthorized_result, Err(ContractError::Unauthorized)),
+            "Non-owner should not update payment params"
+        );
+
+        // Act
+        let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env,
+            testing::mock_info(deployer.as_ref(), &[]),
+            execute_msg,
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to update payment params");
+        let received_response = contract_result.unwrap();
+        let expected_response = Response::default().add_event(
+            Event::new("my-collection-manager")
+                .add_attribute("update-payment-params-beneficiary", beneficiary)
+                .add_attribute("update-payment-params-mint-price-denom", "silver")
+                .add_attribute("update-payment-params-mint-price-amount", "1"),
+        );
+        assert_eq!(received_response, expected_response);
+        let payment_params = PAYMENT_PARAMS
             .load(&mocked_deps_mut.storage)
             .expect("Failed to load payment params");
-        assert_eq!(saved_payment_params, payment_params);
-        assert_contract_version(&mocked_deps_mut.storage, "my-collection-manager", "0.1.0")
-            .expect("Failed to assert contract version");
-        let contract_info = cw2::CONTRACT
-            .load(&mocked_deps_mut.storage)
-            .expect("Failed to load contract info");
-        assert_eq!(
-            contract_info,
-            ContractVersion {
-                contract: "my-collection-manager".to_owned(),
-                version: "0.1.0".to_owned(),
-            }
-        );
+        assert_eq!(payment_params, new_payment_params);
     }
 
     #[test]
-    fn test_pass_through() {
+    fn test_ownership_transfer() {
         // Arrange
-        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_deps_mut = testing::mock_dependencies();
         let mocked_env = testing::mock_env();
         let deployer = Addr::unchecked("deployer");
-        let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
+        let new_owner = Addr::unchecked("new-owner");
         let instantiate_msg = InstantiateMsg {
-            payment_params: PaymentParams {
-                beneficiary: deployer.to_owned(),
-                mint_price: None,
-            },
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config::default(),
         };
         let _ = super::instantiate(
             mocked_deps_mut.as_mut(),
             mocked_env.to_owned(),
-            mocked_msg_info,
+            testing::mock_info(deployer.as_ref(), &[]),
             instantiate_msg,
         )
         .expect("Failed to instantiate manager");
-        let executer = Addr::unchecked("executer");
-        let fund_sent = Coin {
-            denom: "gold".to_owned(),
-            amount: Uint128::from(335u128),
-        };
-        let mocked_msg_info = testing::mock_info(executer.as_ref(), &[fund_sent.to_owned()]);
-        let name = "alice".to_owned();
-        let owner = Addr::unchecked("owner");
-        let inner_msg = CollectionExecuteMsg::Mint {
-            token_id: name.to_owned(),
-            owner: owner.to_string(),
-            token_uri: None,
-            extension: None,
-        };
-        let execute_msg = ExecuteMsg::PassThrough {
-            collection: "collection".to_owned(),
-            message: inner_msg.to_owned(),
-        };
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(deployer.as_ref(), &[]),
+            ExecuteMsg::ProposeOwner {
+                new_owner: new_owner.to_string(),
+            },
+        )
+        .expect("Failed to propose owner");
+        let intruder_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info("intruder", &[]),
+            ExecuteMsg::AcceptOwnership,
+        );
+        assert!(
+            matches!(intruder_result, Err(ContractError::Unauthorized)),
+            "Only the pending owner should accept ownership"
+        );
 
         // Act
         let contract_result = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(new_owner.as_ref(), &[]),
+            ExecuteMsg::AcceptOwnership,
+        );
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to accept ownership");
+        let received_response = contract_result.unwrap();
+        let expected_response = Response::default().add_event(
+            Event::new("my-collection-manager").add_attribute("update-owner", new_owner.to_owned()),
+        );
+        assert_eq!(received_response, expected_response);
+        assert_eq!(
+            OWNER
+                .load(&mocked_deps_mut.storage)
+                .expect("Failed to load owner"),
+            new_owner
+        );
+        assert!(!PENDING_OWNER.exists(&mocked_deps_mut.storage));
+        let _ = super::execute(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            testing::mock_info(new_owner.as_ref(), &[]),
+            ExecuteMsg::RenounceOwnership,
+        )
+        .expect("Failed to renounce ownership");
+        assert!(!OWNER.exists(&mocked_deps_mut.storage));
+        let _ = super::sudo(
             mocked_deps_mut.as_mut(),
             mocked_env,
-            mocked_msg_info,
-            execute_msg,
+            SudoMsg::SetOwner {
+                owner: Some(deployer.to_string()),
+            },
+        )
+        .expect("Failed to sudo set owner");
+        assert_eq!(
+            OWNER
+                .load(&mocked_deps_mut.storage)
+                .expect("Failed to load owner"),
+            deployer
+        );
+    }
+
+    #[test]
+    fn test_sudo_update_payment_params() {
+        // Arrange
+        let mut mocked_deps_mut = testing::mock_dependencies();
+        let mocked_env = testing::mock_env();
+        let beneficiary = Addr::unchecked("beneficiary");
+        let new_payment_params = PaymentParams::new(
+            beneficiary.to_owned(),
+            Some(Coin {
+                denom: "silver".to_owned(),
+                amount: Uint128::one(),
+            }),
         );
+        let sudo_msg = SudoMsg::UpdatePaymentParams(new_payment_params.to_owned());
+
+        // Act
+        let contract_result = super::sudo(mocked_deps_mut.as_mut(), mocked_env, sudo_msg);
 
         // Assert
-        assert!(contract_result.is_ok(), "Failed to pass message through");
+        assert!(contract_result.is_ok(), "Failed to sudo");
         let received_response = contract_result.unwrap();
-        let expected_response = Response::default()
-            .add_message(BankMsg::Send {
-                to_address: executer.to_string(),
-                amount: vec![fund_sent],
-            })
-            .add_submessage(SubMsg {
-                id: ReplyCode::PassThrough as u64,
-                msg: CosmosMsg::<Empty>::Wasm(WasmMsg::Execute {
-                    contract_addr: "collection".to_owned(),
-                    msg: to_json_binary(&inner_msg).expect("Failed to serialize inner message"),
-                    funds: vec![],
-                }),
-                reply_on: ReplyOn::Success,
-                gas_limit: None,
-            })
-            .add_event(
-                Event::new("my-collection-manager").add_attribute("token-count-before", "3"),
-            );
+        let expected_response = Response::default().add_event(
+            Event::new("my-collection-manager")
+                .add_attribute("update-payment-params-beneficiary", beneficiary)
+                .add_attribute("update-payment-params-mint-price-denom", "silver")
+                .add_attribute("update-payment-params-mint-price-amount", "1"),
+        );
         assert_eq!(received_response, expected_response);
+        let payment_params = PAYMENT_PARAMS
+            .load(&mocked_deps_mut.storage)
+            .expect("Failed to load payment params");
+        assert_eq!(payment_params, new_payment_params);
     }
 
     #[test]
-    fn test_paid_mint_pass_through() {
+    fn test_sudo_set_collection_payment_params() {
         // Arrange
         let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
         let mocked_env = testing::mock_env();
-        let beneficiary = Addr::unchecked("beneficiary");
         let deployer = Addr::unchecked("deployer");
         let mocked_msg_info = testing::mock_info(deployer.as_ref(), &[]);
-        let minting_price = Coin {
-            amount: Uint128::from(55u16),
-            denom: "silver".to_owned(),
-        };
         let instantiate_msg = InstantiateMsg {
-            payment_params: PaymentParams {
-                beneficiary: beneficiary.to_owned(),
-                mint_price: Some(minting_price.to_owned()),
-            },
+            owner: None,
+            payment_params: PaymentParams::new(deployer.to_owned(), None),
+            config: Config::default(),
         };
         let _ = super::instantiate(
             mocked_deps_mut.as_mut(),
@@ -471,136 +9076,135 @@ mod tests {
             instantiate_msg,
         )
         .expect("Failed to instantiate manager");
-        let executer = Addr::unchecked("executer");
-        let extra_fund_sent = Coin {
-            denom: "gold".to_owned(),
-            amount: Uint128::from(335u128),
-        };
-        let fistful_silver = Coin {
-            amount: Uint128::from(30u16),
+        add_mock_collection(&mut mocked_deps_mut.storage, "collection");
+        let collection = Addr::unchecked("collection");
+        let beneficiary = Addr::unchecked("beneficiary");
+        let minting_price = Coin {
             denom: "silver".to_owned(),
+            amount: Uint128::from(55u16),
         };
-        let mocked_msg_info = testing::mock_info(
-            executer.as_ref(),
-            &[
-                extra_fund_sent.to_owned(),
-                fistful_silver.to_owned(),
-                fistful_silver,
-            ],
+        let collection_payment_params =
+            PaymentParams::new(beneficiary.to_owned(), Some(minting_price.to_owned()));
+        let sudo_msg = SudoMsg::SetCollectionPaymentParams {
+            collection: collection.to_string(),
+            payment_params: collection_payment_params.to_owned(),
+        };
+
+        // Act
+        let contract_result =
+            super::sudo(mocked_deps_mut.as_mut(), mocked_env.to_owned(), sudo_msg);
+
+        // Assert
+        assert!(contract_result.is_ok(), "Failed to sudo");
+        let received_response = contract_result.unwrap();
+        let expected_response = Response::default().add_event(
+            Event::new("my-collection-manager")
+                .add_attribute("update-collection-payment-params", collection.to_owned())
+                .add_attribute("update-payment-params-beneficiary", beneficiary.to_owned())
+                .add_attribute("update-payment-params-mint-price-denom", "silver")
+                .add_attribute("update-payment-params-mint-price-amount", "55"),
         );
-        let name = "alice".to_owned();
-        let owner = Addr::unchecked("owner");
+        assert_eq!(received_response, expected_response);
+        let saved_payment_params = COLLECTION_PAYMENT_PARAMS
+            .load(&mocked_deps_mut.storage, &collection)
+            .expect("Failed to load collection payment params");
+        assert_eq!(saved_payment_params, collection_payment_params);
+        let executer = Addr::unchecked("executer");
+        let mocked_msg_info = testing::mock_info(executer.as_ref(), &[minting_price.to_owned()]);
         let inner_msg = CollectionExecuteMsg::Mint {
-            token_id: name.to_owned(),
-            owner: owner.to_string(),
+            token_id: "alice".to_owned(),
+            owner: executer.to_string(),
             token_uri: None,
             extension: None,
         };
         let execute_msg = ExecuteMsg::PassThrough {
-            collection: "collection".to_owned(),
+            collection: collection.to_string(),
             message: inner_msg.to_owned(),
+            mint_options: None,
         };
-
-        // Act
-        let contract_result = super::execute(
+        let received_response = super::execute(
             mocked_deps_mut.as_mut(),
             mocked_env,
-            mocked_msg_info.to_owned(),
+            mocked_msg_info,
             execute_msg,
-        );
-
-        // Assert
-        assert!(contract_result.is_ok(), "Failed to pass message through");
-        let received_response = contract_result.unwrap();
-        let expected_denom_change = Coin {
-            amount: Uint128::from(5u16),
-            denom: "silver".to_owned(),
-        };
-        let expected_response = Response::default()
-            .add_message(BankMsg::Send {
+        )
+        .expect("Failed to pass message through");
+        assert_eq!(
+            received_response.messages[0],
+            SubMsg::new(BankMsg::Send {
                 to_address: beneficiary.to_string(),
                 amount: vec![minting_price],
             })
-            .add_message(BankMsg::Send {
-                to_address: mocked_msg_info.sender.to_string(),
-                amount: vec![extra_fund_sent, expected_denom_change],
-            })
-            .add_submessage(SubMsg {
-                id: ReplyCode::PassThrough as u64,
-                msg: CosmosMsg::<Empty>::Wasm(WasmMsg::Execute {
-                    contract_addr: "collection".to_owned(),
-                    msg: to_json_binary(&inner_msg).expect("Failed to serialize inner message"),
-                    funds: vec![],
-                }),
-                reply_on: ReplyOn::Success,
-                gas_limit: None,
-            })
-            .add_event(
-                Event::new("my-collection-manager").add_attribute("token-count-before", "3"),
-            );
-        assert_eq!(received_response, expected_response);
+        );
     }
 
     #[test]
-    fn test_reply_pass_through() {
+    fn test_sudo_remove_collection_payment_params() {
         // Arrange
-        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 3 });
+        let mut mocked_deps_mut = testing::mock_dependencies();
         let mocked_env = testing::mock_env();
-        let num_tokens = to_json_binary(&NameServiceExecuteMsgResponse { num_tokens: 4 })
-            .expect("Failed to serialize counter");
-        let mut prefixed_num_tokens = vec![10, 16];
-        prefixed_num_tokens.extend_from_slice(num_tokens.as_slice());
-        let reply = Reply {
-            id: ReplyCode::PassThrough as u64,
-            result: SubMsgResult::Ok(SubMsgResponse {
-                data: Some(Binary::from(prefixed_num_tokens)),
-                events: vec![],
-            }),
+        let collection = Addr::unchecked("collection");
+        COLLECTION_PAYMENT_PARAMS
+            .save(
+                &mut mocked_deps_mut.storage,
+                &collection,
+                &PaymentParams::new(Addr::unchecked("beneficiary"), None),
+            )
+            .expect("Failed to save collection payment params");
+        let sudo_msg = SudoMsg::RemoveCollectionPaymentParams {
+            collection: collection.to_string(),
         };
 
         // Act
-        let contract_result = super::reply(mocked_deps_mut.as_mut(), mocked_env, reply);
+        let contract_result = super::sudo(mocked_deps_mut.as_mut(), mocked_env, sudo_msg);
 
         // Assert
-        assert!(contract_result.is_ok(), "Failed to pass reply through");
+        assert!(contract_result.is_ok(), "Failed to sudo");
         let received_response = contract_result.unwrap();
-        let expected_response = Response::default()
-            .add_event(Event::new("my-collection-manager").add_attribute("token-count-after", "4"));
+        let expected_response = Response::default().add_event(
+            Event::new("my-collection-manager")
+                .add_attribute("remove-collection-payment-params", collection.to_owned()),
+        );
         assert_eq!(received_response, expected_response);
+        assert!(!COLLECTION_PAYMENT_PARAMS.has(&mocked_deps_mut.storage, &collection));
     }
 
     #[test]
-    fn test_sudo_update_payment_params() {
+    fn test_sudo_add_collection() {
         // Arrange
-        let mut mocked_deps_mut = testing::mock_dependencies();
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 0 });
         let mocked_env = testing::mock_env();
-        let beneficiary = Addr::unchecked("beneficiary");
-        let new_payment_params = PaymentParams {
-            beneficiary: beneficiary.to_owned(),
-            mint_price: Some(Coin {
-                denom: "silver".to_owned(),
-                amount: Uint128::one(),
-            }),
+        let collection = Addr::unchecked("collection");
+        let sudo_msg = SudoMsg::AddCollection {
+            collection: collection.to_string(),
+            label: "my names".to_owned(),
         };
-        let sudo_msg = SudoMsg::UpdatePaymentParams(new_payment_params.to_owned());
 
         // Act
-        let contract_result = super::sudo(mocked_deps_mut.as_mut(), mocked_env, sudo_msg);
+        let contract_result =
+            super::sudo(mocked_deps_mut.as_mut(), mocked_env.to_owned(), sudo_msg);
 
         // Assert
         assert!(contract_result.is_ok(), "Failed to sudo");
         let received_response = contract_result.unwrap();
         let expected_response = Response::default().add_event(
             Event::new("my-collection-manager")
-                .add_attribute("update-payment-params-beneficiary", beneficiary)
-                .add_attribute("update-payment-params-mint-price-denom", "silver")
-                .add_attribute("update-payment-params-mint-price-amount", "1"),
+                .add_attribute("add-collection", collection.to_owned())
+                .add_attribute("add-collection-label", "my names")
+                .add_attribute("add-collection-code-id", MOCK_CODE_ID.to_string()),
         );
         assert_eq!(received_response, expected_response);
-        let payment_params = PAYMENT_PARAMS
-            .load(&mocked_deps_mut.storage)
-            .expect("Failed to load payment params");
-        assert_eq!(payment_params, new_payment_params);
+        let collection_info = COLLECTIONS
+            .load(&mocked_deps_mut.storage, &collection)
+            .expect("Failed to load collection info");
+        assert_eq!(
+            collection_info,
+            CollectionInfo {
+                label: "my names".to_owned(),
+                code_id: MOCK_CODE_ID,
+                created_at: mocked_env.block.time,
+            }
+        );
     }
 
     #[test]
@@ -609,15 +9213,17 @@ mod tests {
         let mut mocked_deps_mut = testing::mock_dependencies();
         let mocked_env = testing::mock_env();
         let beneficiary = Addr::unchecked("beneficiary");
-        let new_payment_params = PaymentParams {
-            beneficiary: beneficiary.to_owned(),
-            mint_price: Some(Coin {
+        let new_payment_params = PaymentParams::new(
+            beneficiary.to_owned(),
+            Some(Coin {
                 denom: "silver".to_owned(),
                 amount: Uint128::one(),
             }),
-        };
+        );
         let migrate_msg = MigrateMsg {
             payment_params: new_payment_params.to_owned(),
+            owner: Some("owner".to_owned()),
+            collections: vec![],
         };
 
         // Act
@@ -628,7 +9234,8 @@ mod tests {
         let received_response = result.unwrap();
         let expected_response = Response::default().add_event(
             Event::new("my-collection-manager")
-                .add_attribute("update-contract-version", "0.1.0")
+                .add_attribute("update-contract-version", "0.2.0")
+                .add_attribute("update-owner", "owner")
                 .add_attribute("update-payment-params-beneficiary", beneficiary)
                 .add_attribute("update-payment-params-mint-price-denom", "silver")
                 .add_attribute("update-payment-params-mint-price-amount", "1"),
@@ -638,7 +9245,7 @@ mod tests {
             .load(&mocked_deps_mut.storage)
             .expect("Failed to load payment params");
         assert_eq!(new_payment_params, saved_payment_params);
-        assert_contract_version(&mocked_deps_mut.storage, "my-collection-manager", "0.1.0")
+        assert_contract_version(&mocked_deps_mut.storage, "my-collection-manager", "0.2.0")
             .expect("Failed to assert contract version");
         let contract_info = cw2::CONTRACT
             .load(&mocked_deps_mut.storage)
@@ -647,8 +9254,87 @@ mod tests {
             contract_info,
             ContractVersion {
                 contract: "my-collection-manager".to_owned(),
-                version: "0.1.0".to_owned(),
+                version: "0.2.0".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_migrate_from_0_1_0() {
+        // Arrange
+        let mut mocked_deps_mut = mock_deps(NumTokensResponse { count: 0 });
+        let mocked_env = testing::mock_env();
+        set_contract_version(
+            &mut mocked_deps_mut.storage,
+            "my-collection-manager",
+            "0.1.0",
+        )
+        .expect("Failed to set contract version");
+        let ownerless_msg = MigrateMsg {
+            payment_params: PaymentParams::new(Addr::unchecked("beneficiary"), None),
+            owner: None,
+            collections: vec![],
+        };
+        let migrate_msg = MigrateMsg {
+            payment_params: PaymentParams::new(Addr::unchecked("beneficiary"), None),
+            owner: Some("owner".to_owned()),
+            collections: vec![MigrateCollection {
+                collection: "collection".to_owned(),
+                label: "my names".to_owned(),
+            }],
+        };
+
+        // Act
+        let ownerless_result = super::migrate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            ownerless_msg,
+        );
+        let result = super::migrate(
+            mocked_deps_mut.as_mut(),
+            mocked_env.to_owned(),
+            migrate_msg.to_owned(),
+        );
+        let second_result =
+            super::migrate(mocked_deps_mut.as_mut(), mocked_env.to_owned(), migrate_msg);
+
+        // Assert
+        assert!(
+            matches!(ownerless_result, Err(ContractError::MissingOwner)),
+            "Expected MissingOwner, got {:?}",
+            ownerless_result
+        );
+        assert!(result.is_ok(), "Failed to migrate manager from 0.1.0");
+        assert_eq!(
+            OWNER
+                .load(&mocked_deps_mut.storage)
+                .expect("Failed to load owner"),
+            Addr::unchecked("owner")
+        );
+        assert_eq!(
+            COLLECTIONS
+                .load(&mocked_deps_mut.storage, &Addr::unchecked("collection"))
+                .expect("Failed to load collection info"),
+            CollectionInfo {
+                label: "my names".to_owned(),
+                code_id: MOCK_CODE_ID,
+                created_at: mocked_env.block.time,
             }
         );
+        assert_eq!(
+            CONFIG
+                .load(&mocked_deps_mut.storage)
+                .expect("Failed to load config"),
+            Config::default()
+        );
+        assert_contract_version(&mocked_deps_mut.storage, "my-collection-manager", "0.2.0")
+            .expect("Failed to assert contract version");
+        match second_result {
+            Err(ContractError::Version(VersionError::WrongVersion { expected, found })) => {
+                assert_eq!(expected, "0.1.0");
+                assert_eq!(found, "0.2.0");
+            }
+            _ => panic!("Expected WrongVersion, got {:?}", second_result),
+        }
     }
 }
@@ -1,5 +1,7 @@
-use cosmwasm_std::{Coin, StdError};
+use cosmwasm_std::{Coin, StdError, Timestamp, Uint128};
 use cw2::VersionError;
+use cw20::Cw20CoinVerified;
+use cw_utils::ParseReplyError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,8 +10,187 @@ pub enum ContractError {
     Std(#[from] StdError),
     #[error("price cannot be zero")]
     ZeroPrice,
-    #[error("missing payment {:?}", missing_payment)]
-    MissingPayment { missing_payment: Coin },
+    #[error("missing payment, accepted {:?}", accepted_payments)]
+    MissingPayment { accepted_payments: Vec<Coin> },
+    #[error("missing cw20 payment, accepted {:?}", accepted_payments)]
+    MissingCw20Payment {
+        accepted_payments: Vec<Cw20CoinVerified>,
+    },
+    #[error("cw20 token {token} is not accepted")]
+    UnacceptedCw20 { token: String },
+    #[error("only mint can be paid with cw20 tokens")]
+    Cw20PassThroughNotMint,
+    #[error("alternative mint prices need a mint price")]
+    AlternativePricesWithoutMintPrice,
+    #[error("more than one mint price in {denom}")]
+    DuplicatePriceDenom { denom: String },
+    #[error("more than one length price for {min_length} characters")]
+    DuplicateLengthPrice { min_length: u32 },
+    #[error("token id is shorter than the shortest length price of {min_length} characters")]
+    TokenIdBelowLengthPrices { min_length: u32 },
+    #[error("length prices cannot be combined with cw20 prices")]
+    LengthPricesWithCw20Prices,
+    #[error("yearly prices cannot be combined with cw20 prices")]
+    YearlyPriceWithCw20Prices,
+    #[error("price in {denom} does not match the denom of the yearly price")]
+    YearlyPriceDenomMismatch { denom: String },
+    #[error("mints on {collection} in one batch share no payment denom")]
+    BatchMintDenomMismatch { collection: String },
+    #[error("revenue share of {recipient} has zero weight")]
+    ZeroWeight { recipient: String },
+    #[error("revenue split lists {recipient} more than once")]
+    DuplicateRecipient { recipient: String },
+    #[error("revenue split weights overflow")]
+    WeightOverflow,
+    #[error("unauthorized")]
+    Unauthorized,
+    #[error("no pending ownership transfer")]
+    NoPendingOwner,
+    #[error("migration needs an owner for an instance that has none")]
+    MissingOwner,
+    #[error("collection {collection} is not registered")]
+    UnregisteredCollection { collection: String },
+    #[error("batch has no items")]
+    EmptyBatch,
+    #[error("batch exceeds the maximum of {max_batch_size} items")]
+    BatchTooLarge { max_batch_size: u32 },
+    #[error("fee of {fee_bps} basis points exceeds 100%")]
+    InvalidFeeBps { fee_bps: u16 },
+    #[error("mint phase {name} must end after it starts")]
+    InvalidMintPhaseWindow { name: String },
+    #[error("mint phase {name} has a merkle root that is not a sha256 hash")]
+    InvalidMerkleRoot { name: String },
+    #[error("mint phase {name} has a per address limit of zero")]
+    ZeroMintPhaseLimit { name: String },
+    #[error("more than one mint phase named {name}")]
+    DuplicateMintPhase { name: String },
+    #[error("mint phase {name} overlaps mint phase {other}")]
+    OverlappingMintPhases { name: String, other: String },
+    #[error("merkle proof does not allow the minter in mint phase {phase}")]
+    InvalidMerkleProof { phase: String },
+    #[error("minter reached the limit of {limit} mints in mint phase {phase}")]
+    MintPhaseLimitReached { phase: String, limit: u32 },
+    #[error("mint limits cannot be zero")]
+    ZeroMintLimit,
+    #[error("minter reached the limit of {limit} mints on the collection")]
+    AddressMintLimitReached { limit: u32 },
+    #[error("collection reached its maximum supply of {max_supply}")]
+    MaxSupplyReached { max_supply: u64 },
+    #[error(
+        "token id policy has a max length of {max_length} below its min length of {min_length}"
+    )]
+    InvalidTokenIdLengths { min_length: u32, max_length: u32 },
+    #[error("token id {token_id} is shorter than {min_length} characters")]
+    TokenIdTooShort { token_id: String, min_length: u32 },
+    #[error("token id {token_id} is longer than {max_length} characters")]
+    TokenIdTooLong { token_id: String, max_length: u32 },
+    #[error("token id {token_id} contains the disallowed character {character:?}")]
+    TokenIdInvalidChar { token_id: String, character: char },
+    #[error("token id {token_id} is reserved")]
+    TokenIdReserved { token_id: String },
+    #[error("commit-reveal window of {min_blocks} to {max_blocks} blocks is empty")]
+    InvalidCommitRevealWindow { min_blocks: u64, max_blocks: u64 },
+    #[error("commit-reveal is not enabled")]
+    CommitRevealDisabled,
+    #[error("mints have to be committed to and revealed")]
+    CommitRevealRequired,
+    #[error("commitment has to be {len} bytes long")]
+    InvalidCommitmentLength { len: usize },
+    #[error("commit has to carry exactly the deposit of {:?}", deposit)]
+    InvalidCommitDeposit { deposit: Vec<Coin> },
+    #[error("commitment already exists")]
+    CommitmentExists,
+    #[error("no commitment matches the revealed mint")]
+    UnknownCommitment,
+    #[error("commitment can only be revealed from height {reveal_from}")]
+    RevealTooEarly { reveal_from: u64 },
+    #[error("commitment expired at height {expired_at}")]
+    CommitmentExpired { expired_at: u64 },
+    #[error("only mints can be revealed")]
+    RevealNotMint,
+    #[error("registrations last from 1 to {max_years} years")]
+    InvalidRegistrationYears { max_years: u32 },
+    #[error("token id {token_id} is registered until {expires_at}")]
+    AlreadyRegistered {
+        token_id: String,
+        expires_at: Timestamp,
+    },
+    #[error("token id {token_id} is not registered")]
+    NoRegistration { token_id: String },
+    #[error("registration of {token_id} expired at {expires_at}")]
+    RegistrationExpired {
+        token_id: String,
+        expires_at: Timestamp,
+    },
+    #[error("registration of {token_id} only expires at {expires_at}")]
+    RegistrationNotExpired {
+        token_id: String,
+        expires_at: Timestamp,
+    },
+    #[error("collection {collection} has no yearly price to renew at")]
+    RenewalNotPriced { collection: String },
+    #[error("auction {auction_id} does not exist")]
+    UnknownAuction { auction_id: u64 },
+    #[error("token id {token_id} is already auctioned")]
+    AuctionExists { token_id: String },
+    #[error("token id {token_id} is auctioned and can only be minted by settling the auction")]
+    TokenIdInAuction { token_id: String },
+    #[error("auction has to end in the future")]
+    InvalidAuctionEnd,
+    #[error("bid increment cannot be zero")]
+    ZeroBidIncrement,
+    #[error("auction {auction_id} has ended")]
+    AuctionEnded { auction_id: u64 },
+    #[error("auction only ends at {end}")]
+    AuctionNotEnded { end: Timestamp },
+    #[error("bid has to be at least {min_bid}")]
+    BidTooLow { min_bid: Coin },
+    #[error("bid has to be paid in {denom} only")]
+    InvalidBidFunds { denom: String },
+    #[error("dutch auction price cannot be combined with other mint prices")]
+    DutchAuctionWithOtherPrices,
+    #[error("dutch auction end price cannot be above its start price")]
+    RisingDutchAuctionPrice,
+    #[error("dutch auction duration and steps cannot be zero")]
+    ZeroDutchAuctionDuration,
+    #[error("dutch auction price declines until {ends_at}")]
+    DutchAuctionNotOver { ends_at: Timestamp },
+    #[error("nothing held for {minter}")]
+    NoDutchEscrow { minter: String },
+    #[error("mints are priced by the mint prices, not by action fees")]
+    ActionFeeForMint,
+    #[error("only burns can have a rebate, not {action}")]
+    RebateNotBurn { action: String },
+    #[error("rebate pool of {collection} holds less than {amount}")]
+    RebatePoolTooLow { collection: String, amount: Coin },
+    #[error("no funds sent to the rebate pool")]
+    NoRebatePoolFunds,
+    #[error("duplicate fee for {action}")]
+    DuplicateActionFee { action: String },
+    #[error("duplicate rule for {action}")]
+    DuplicateActionRule { action: String },
+    #[error("only the owner can pass {action} through")]
+    OwnerOnlyAction { action: String },
+    #[error("{action} cannot be passed through to {collection}")]
+    ForbiddenAction { action: String, collection: String },
+    #[error("{sender} does not own token id {token_id}")]
+    NotTokenOwner { sender: String, token_id: String },
+    #[error("{collection} is paused")]
+    Paused { collection: String },
+    #[error("nothing to withdraw")]
+    NothingToWithdraw,
+    #[error("only {claimable}{denom} can be withdrawn")]
+    InsufficientClaimable { denom: String, claimable: Uint128 },
+    #[error("bonding curve cannot be combined with other mint prices")]
+    BondingCurveWithOtherPrices,
+    #[error("duplicate bonding curve step at supply {min_supply}")]
+    DuplicateSupplyStep { min_supply: u64 },
+    #[error("no collection code id configured")]
+    MissingCollectionCodeId,
+    #[error("unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
     #[error("{0}")]
     Version(#[from] VersionError),
 }
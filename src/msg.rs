@@ -1,30 +1,900 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Empty, Uint128};
-use cw721::msg::{Cw721ExecuteMsg, Cw721QueryMsg};
+use cosmwasm_std::{
+    Addr, Coin, Decimal, Empty, HexBinary, StdError, StdResult, Timestamp, Uint128,
+};
+use cw20::{Cw20CoinVerified, Cw20ReceiveMsg};
+use cw721::msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721QueryMsg};
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
 
 #[cw_serde]
 pub struct InstantiateMsg {
+    pub owner: Option<String>,
     pub payment_params: PaymentParams,
+    pub config: Config,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Config {
+    pub collection_code_id: Option<u64>,
+    /// Caps the items of a `BatchPassThrough`, falls back to a built-in default when unset.
+    #[serde(default)]
+    pub max_batch_size: Option<u32>,
+    #[serde(default)]
+    pub mint_failure_policy: MintFailurePolicy,
+    /// Enables `CommitMint` and `RevealMint` when set.
+    #[serde(default)]
+    pub commit_reveal: Option<CommitRevealConfig>,
+    /// Keeps native revenue in the manager for the payees to `Withdraw`, instead of sending it
+    /// along with every payment. Cw20 revenue is always transferred right away.
+    #[serde(default)]
+    pub accrue_revenue: bool,
+}
+
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+/// What happens to a native mint payment when the collection rejects the mint.
+#[cw_serde]
+#[derive(Default)]
+pub enum MintFailurePolicy {
+    /// The whole transaction reverts.
+    #[default]
+    Revert,
+    /// The failure is reported and the payment refunded, minus a fee in basis points that goes to
+    /// the beneficiaries.
+    Refund { fee_bps: u16 },
+}
+
+#[cw_serde]
+pub struct CommitRevealConfig {
+    /// Blocks to wait after a commit before it can be revealed.
+    pub min_blocks: u64,
+    /// Blocks after a commit at which it expires.
+    pub max_blocks: u64,
+    /// Native coin to send along with each commit.
+    pub deposit: Option<Coin>,
+    #[serde(default)]
+    pub deposit_refund: CommitDepositRefund,
+    /// Rejects mints that were not committed to first.
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// When a commit deposit goes back to the committer, forfeited deposits go to the beneficiary.
+#[cw_serde]
+#[derive(Default)]
+pub enum CommitDepositRefund {
+    /// Refunded on reveal and on expiry.
+    Always,
+    #[default]
+    OnReveal,
+    Never,
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if let MintFailurePolicy::Refund { fee_bps } = self.mint_failure_policy {
+            if MAX_FEE_BPS < fee_bps {
+                return Err(ContractError::InvalidFeeBps { fee_bps });
+            }
+        }
+        if let Some(commit_reveal) = &self.commit_reveal {
+            if commit_reveal.max_blocks <= commit_reveal.min_blocks {
+                return Err(ContractError::InvalidCommitRevealWindow {
+                    min_blocks: commit_reveal.min_blocks,
+                    max_blocks: commit_reveal.max_blocks,
+                });
+            }
+            if matches!(&commit_reveal.deposit, Some(deposit) if deposit.amount.is_zero()) {
+                return Err(ContractError::ZeroPrice);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub const MINT_COMMITMENT_LEN: usize = 32;
+
+/// The commitment `RevealMint` expects for a mint: the sha256 of the token id, owner, salt and
+/// sender, each preceded by its length as a big-endian u32.
+pub fn mint_commitment(token_id: &str, owner: &str, salt: &[u8], sender: &str) -> HexBinary {
+    let hash = [
+        token_id.as_bytes(),
+        owner.as_bytes(),
+        salt,
+        sender.as_bytes(),
+    ]
+    .iter()
+    .fold(Sha256::new(), |hash, part| {
+        hash.chain_update((part.len() as u32).to_be_bytes())
+            .chain_update(part)
+    })
+    .finalize();
+    HexBinary::from(hash.as_slice())
+}
+
+#[cw_serde]
+pub struct MintCommitment {
+    pub sender: Addr,
+    /// Block height of the commit.
+    pub height: u64,
+    pub deposit: Option<Coin>,
 }
 
 #[cw_serde]
 pub struct PaymentParams {
+    /// Receives the whole payment when `revenue_split` is empty, and the rounding dust otherwise.
     pub beneficiary: Addr,
     pub mint_price: Option<Coin>,
+    #[serde(default)]
+    pub revenue_split: Vec<RevenueShare>,
+    /// Prices in other denoms, any one of which can be paid instead of `mint_price`.
+    #[serde(default)]
+    pub alternative_mint_prices: Vec<Coin>,
+    /// Prices in whitelisted cw20 tokens, paid through the `Receive` hook.
+    #[serde(default)]
+    pub cw20_mint_prices: Vec<Cw20CoinVerified>,
+    /// Prices by length of the token id, which take precedence over the other native prices.
+    #[serde(default)]
+    pub length_prices: Vec<LengthPrice>,
+    /// Registers minted token ids for the years paid at this rate, on top of the mint price.
+    #[serde(default)]
+    pub yearly_price: Option<Coin>,
+    /// Replaces `mint_price` with a price that declines over time.
+    #[serde(default)]
+    pub dutch_auction: Option<DutchAuctionPrice>,
+    /// Replaces `mint_price` with a price that grows with the supply of the collection.
+    #[serde(default)]
+    pub bonding_curve: Option<BondingCurve>,
+    /// Fees for passing actions other than mints through.
+    #[serde(default)]
+    pub action_fees: Vec<ActionFee>,
+}
+
+#[cw_serde]
+pub struct ActionFee {
+    pub action: CollectionAction,
+    pub price: Coin,
+    /// Shares of the fee, which go by the revenue split of the payment params when empty.
+    #[serde(default)]
+    pub revenue_split: Vec<RevenueShare>,
+    /// Pays `price` out of the rebate pool of the collection instead of charging it, split like a
+    /// fee whose beneficiary is the sender. Only burns can have a rebate.
+    #[serde(default)]
+    pub rebate: bool,
+}
+
+#[cw_serde]
+pub struct BondingCurve {
+    /// The price of the first token of the collection.
+    pub base_price: Coin,
+    pub shape: CurveShape,
+}
+
+#[cw_serde]
+pub enum CurveShape {
+    /// Adds `slope` to the price for every token in the collection.
+    Linear { slope: Uint128 },
+    /// Compounds the price by `growth_bps` for every token in the collection.
+    Exponential { growth_bps: u32 },
+    /// Charges the price of the highest step the supply reached, and the base price below them.
+    Steps { steps: Vec<SupplyStep> },
+}
+
+#[cw_serde]
+pub struct SupplyStep {
+    pub min_supply: u64,
+    pub price: Uint128,
+}
+
+impl BondingCurve {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.base_price.amount.is_zero() {
+            return Err(ContractError::ZeroPrice);
+        }
+        if let CurveShape::Steps { steps } = &self.shape {
+            for (index, step) in steps.iter().enumerate() {
+                if step.price.is_zero() {
+                    return Err(ContractError::ZeroPrice);
+                }
+                if steps[..index]
+                    .iter()
+                    .any(|other| other.min_supply == step.min_supply)
+                {
+                    return Err(ContractError::DuplicateSupplyStep {
+                        min_supply: step.min_supply,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The price of the mint that comes after `supply` tokens.
+    pub fn price_at(&self, supply: u64) -> StdResult<Coin> {
+        let base_price = self.base_price.amount;
+        let amount = match &self.shape {
+            CurveShape::Linear { slope } => {
+                base_price.checked_add(slope.checked_mul(supply.into())?)?
+            }
+            CurveShape::Exponential { growth_bps } => {
+                let growth = Decimal::from_ratio(10_000 + u128::from(*growth_bps), 10_000u128)
+                    .checked_pow(u32::try_from(supply).unwrap_or(u32::MAX))?;
+                base_price
+                    .checked_mul_floor(growth)
+                    .map_err(|err| StdError::generic_err(err.to_string()))?
+            }
+            CurveShape::Steps { steps } => steps
+                .iter()
+                .filter(|step| step.min_supply <= supply)
+                .max_by_key(|step| step.min_supply)
+                .map_or(base_price, |step| step.price),
+        };
+        Ok(Coin {
+            denom: self.base_price.denom.to_owned(),
+            amount,
+        })
+    }
+}
+
+/// A mint price that declines from `start_price` to `end_price` over `duration` seconds.
+#[cw_serde]
+pub struct DutchAuctionPrice {
+    pub start_price: Coin,
+    /// The price the decline stops at, in the denom of `start_price`.
+    pub end_price: Uint128,
+    pub start: Timestamp,
+    pub duration: u64,
+    pub decay: PriceDecay,
+    /// Holds the part of each payment above `end_price`, so minters can be refunded down to the
+    /// clearing price once the decline is over.
+    #[serde(default)]
+    pub refund_to_clearing_price: bool,
+}
+
+#[cw_serde]
+pub enum PriceDecay {
+    Linear,
+    /// Drops the price once every `step` seconds.
+    Stepwise {
+        step: u64,
+    },
+}
+
+impl DutchAuctionPrice {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.start_price.amount.is_zero() || self.end_price.is_zero() {
+            return Err(ContractError::ZeroPrice);
+        }
+        if self.end_price > self.start_price.amount {
+            return Err(ContractError::RisingDutchAuctionPrice);
+        }
+        if self.duration == 0 || matches!(self.decay, PriceDecay::Stepwise { step: 0 }) {
+            return Err(ContractError::ZeroDutchAuctionDuration);
+        }
+        Ok(())
+    }
+
+    pub fn ends_at(&self) -> Timestamp {
+        self.start.plus_seconds(self.duration)
+    }
+
+    /// The price at `now`, which is the start price before the start and the end price after
+    /// the end.
+    pub fn price_at(&self, now: Timestamp) -> Coin {
+        let elapsed = now
+            .seconds()
+            .saturating_sub(self.start.seconds())
+            .min(self.duration);
+        let elapsed = match self.decay {
+            PriceDecay::Stepwise { step } if elapsed < self.duration => elapsed - elapsed % step,
+            _ => elapsed,
+        };
+        let decline =
+            (self.start_price.amount - self.end_price).multiply_ratio(elapsed, self.duration);
+        Coin {
+            denom: self.start_price.denom.to_owned(),
+            amount: self.start_price.amount - decline,
+        }
+    }
+}
+
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+pub const MAX_REGISTRATION_YEARS: u32 = 100;
+
+/// A token id registered until it expires.
+#[cw_serde]
+pub struct Registration {
+    pub collection: Addr,
+    pub token_id: String,
+    pub expires_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct LengthPrice {
+    /// Applies to token ids of at least this many characters, up to the next tier.
+    pub min_length: u32,
+    pub price: Coin,
+}
+
+#[cw_serde]
+pub struct RevenueShare {
+    pub recipient: Addr,
+    pub weight: u64,
+}
+
+fn validate_revenue_split(revenue_split: &[RevenueShare]) -> Result<(), ContractError> {
+    let mut total_weight = 0u64;
+    for (index, share) in revenue_split.iter().enumerate() {
+        if share.weight == 0 {
+            return Err(ContractError::ZeroWeight {
+                recipient: share.recipient.to_string(),
+            });
+        }
+        if revenue_split[..index]
+            .iter()
+            .any(|other| other.recipient == share.recipient)
+        {
+            return Err(ContractError::DuplicateRecipient {
+                recipient: share.recipient.to_string(),
+            });
+        }
+        total_weight = total_weight
+            .checked_add(share.weight)
+            .ok_or(ContractError::WeightOverflow)?;
+    }
+    Ok(())
 }
 
 impl PaymentParams {
+    /// Params with a single price, or free mints, and none of the optional pricing.
+    pub fn new(beneficiary: Addr, mint_price: Option<Coin>) -> Self {
+        Self {
+            beneficiary,
+            mint_price,
+            revenue_split: vec![],
+            alternative_mint_prices: vec![],
+            cw20_mint_prices: vec![],
+            length_prices: vec![],
+            yearly_price: None,
+            dutch_auction: None,
+            bonding_curve: None,
+            action_fees: vec![],
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.mint_price.is_none() && !self.alternative_mint_prices.is_empty() {
+            return Err(ContractError::AlternativePricesWithoutMintPrice);
+        }
+        if let Some(dutch_auction) = &self.dutch_auction {
+            dutch_auction.validate()?;
+            if self.mint_price.is_some()
+                || !self.cw20_mint_prices.is_empty()
+                || !self.length_prices.is_empty()
+            {
+                return Err(ContractError::DutchAuctionWithOtherPrices);
+            }
+        }
+        if let Some(bonding_curve) = &self.bonding_curve {
+            bonding_curve.validate()?;
+            if self.mint_price.is_some()
+                || !self.cw20_mint_prices.is_empty()
+                || !self.length_prices.is_empty()
+                || self.dutch_auction.is_some()
+            {
+                return Err(ContractError::BondingCurveWithOtherPrices);
+            }
+        }
+        let accepted_prices = self.accepted_mint_prices();
+        for (index, price) in accepted_prices.iter().enumerate() {
+            if price.amount.le(&Uint128::zero()) {
+                return Err(ContractError::ZeroPrice);
+            }
+            if accepted_prices[..index]
+                .iter()
+                .any(|other| other.denom == price.denom)
+            {
+                return Err(ContractError::DuplicatePriceDenom {
+                    denom: price.denom.to_owned(),
+                });
+            }
+        }
+        for (index, price) in self.cw20_mint_prices.iter().enumerate() {
+            if price.amount.is_zero() {
+                return Err(ContractError::ZeroPrice);
+            }
+            if self.cw20_mint_prices[..index]
+                .iter()
+                .any(|other| other.address == price.address)
+            {
+                return Err(ContractError::DuplicatePriceDenom {
+                    denom: price.address.to_string(),
+                });
+            }
+        }
+        if !self.length_prices.is_empty() && !self.cw20_mint_prices.is_empty() {
+            return Err(ContractError::LengthPricesWithCw20Prices);
+        }
+        for (index, tier) in self.length_prices.iter().enumerate() {
+            if tier.price.amount.is_zero() {
+                return Err(ContractError::ZeroPrice);
+            }
+            if self.length_prices[..index]
+                .iter()
+                .any(|other| other.min_length == tier.min_length)
+            {
+                return Err(ContractError::DuplicateLengthPrice {
+                    min_length: tier.min_length,
+                });
+            }
+        }
+        if let Some(yearly_price) = &self.yearly_price {
+            if yearly_price.amount.is_zero() {
+                return Err(ContractError::ZeroPrice);
+            }
+            if !self.cw20_mint_prices.is_empty() {
+                return Err(ContractError::YearlyPriceWithCw20Prices);
+            }
+            if let Some(price) = accepted_prices
+                .iter()
+                .chain(self.length_prices.iter().map(|tier| &tier.price))
+                .chain(self.dutch_auction.iter().map(|dutch| &dutch.start_price))
+                .chain(self.bonding_curve.iter().map(|curve| &curve.base_price))
+                .find(|price| price.denom != yearly_price.denom)
+            {
+                return Err(ContractError::YearlyPriceDenomMismatch {
+                    denom: price.denom.to_owned(),
+                });
+            }
+        }
+        for (index, fee) in self.action_fees.iter().enumerate() {
+            if fee.action == CollectionAction::Mint {
+                return Err(ContractError::ActionFeeForMint);
+            }
+            if fee.price.amount.is_zero() {
+                return Err(ContractError::ZeroPrice);
+            }
+            if self.action_fees[..index]
+                .iter()
+                .any(|other| other.action == fee.action)
+            {
+                return Err(ContractError::DuplicateActionFee {
+                    action: fee.action.as_str().to_owned(),
+                });
+            }
+            if fee.rebate && fee.action != CollectionAction::Burn {
+                return Err(ContractError::RebateNotBurn {
+                    action: fee.action.as_str().to_owned(),
+                });
+            }
+            validate_revenue_split(&fee.revenue_split)?;
+        }
+        validate_revenue_split(&self.revenue_split)
+    }
+
+    pub fn accepted_mint_prices(&self) -> Vec<Coin> {
+        self.mint_price
+            .iter()
+            .chain(self.alternative_mint_prices.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// The native prices of minting `token_id`, any one of which can be paid. Token ids shorter
+    /// than every length price fall back to `mint_price`, and cannot be minted without one.
+    pub fn mint_prices_for(&self, token_id: &str) -> Result<Vec<Coin>, ContractError> {
+        let length = token_id.chars().count();
+        match self
+            .length_prices
+            .iter()
+            .filter(|tier| tier.min_length as usize <= length)
+            .max_by_key(|tier| tier.min_length)
+        {
+            Some(tier) => Ok(vec![tier.price.to_owned()]),
+            None => match self.length_prices.iter().map(|tier| tier.min_length).min() {
+                Some(min_length) if self.mint_price.is_none() => {
+                    Err(ContractError::TokenIdBelowLengthPrices { min_length })
+                }
+                _ => Ok(self.accepted_mint_prices()),
+            },
+        }
+    }
+
+    /// Adds the registration of the token id for `years` to its mint prices, when token ids are
+    /// priced by the year.
+    pub fn registration_prices(
+        &self,
+        mint_prices: Vec<Coin>,
+        years: u32,
+    ) -> Result<Vec<Coin>, ContractError> {
+        let Some(yearly_price) = &self.yearly_price else {
+            return Ok(mint_prices);
+        };
+        let registration = Coin {
+            denom: yearly_price.denom.to_owned(),
+            amount: yearly_price
+                .amount
+                .checked_mul(years.into())
+                .map_err(StdError::from)?,
+        };
+        if mint_prices.is_empty() {
+            return Ok(vec![registration]);
+        }
+        mint_prices
+            .into_iter()
+            .map(|price| {
+                if price.denom != registration.denom {
+                    return Err(ContractError::YearlyPriceDenomMismatch { denom: price.denom });
+                }
+                Ok(Coin {
+                    amount: price
+                        .amount
+                        .checked_add(registration.amount)
+                        .map_err(StdError::from)?,
+                    denom: price.denom,
+                })
+            })
+            .collect()
+    }
+
+    pub fn total_weight(&self) -> u64 {
+        self.revenue_split.iter().map(|share| share.weight).sum()
+    }
+}
+
+/// A window during which minting on a collection is restricted, typically a presale.
+#[cw_serde]
+pub struct MintPhase {
+    pub name: String,
+    pub start: Timestamp,
+    /// Exclusive.
+    pub end: Timestamp,
+    /// Root of the merkle tree of the addresses allowed to mint, anyone can mint when unset.
+    pub merkle_root: Option<HexBinary>,
+    /// Overrides `PaymentParams.mint_price` while the phase is active.
+    pub mint_price: Option<Coin>,
+    pub per_address_limit: Option<u32>,
+}
+
+pub const MERKLE_HASH_LEN: usize = 32;
+
+pub fn validate_mint_phases(phases: &[MintPhase]) -> Result<(), ContractError> {
+    for (index, phase) in phases.iter().enumerate() {
+        if phase.end <= phase.start {
+            return Err(ContractError::InvalidMintPhaseWindow {
+                name: phase.name.to_owned(),
+            });
+        }
+        if let Some(merkle_root) = &phase.merkle_root {
+            if merkle_root.len() != MERKLE_HASH_LEN {
+                return Err(ContractError::InvalidMerkleRoot {
+                    name: phase.name.to_owned(),
+                });
+            }
+        }
+        if let Some(mint_price) = &phase.mint_price {
+            if mint_price.amount.is_zero() {
+                return Err(ContractError::ZeroPrice);
+            }
+        }
+        if phase.per_address_limit == Some(0) {
+            return Err(ContractError::ZeroMintPhaseLimit {
+                name: phase.name.to_owned(),
+            });
+        }
+        for other in &phases[..index] {
+            if other.name == phase.name {
+                return Err(ContractError::DuplicateMintPhase {
+                    name: phase.name.to_owned(),
+                });
+            }
+            if other.start < phase.end && phase.start < other.end {
+                return Err(ContractError::OverlappingMintPhases {
+                    name: phase.name.to_owned(),
+                    other: other.name.to_owned(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Caps on the mints that go through the manager.
+#[cw_serde]
+#[derive(Default)]
+pub struct MintLimits {
+    /// Counted per sender of the mint, not per owner of the minted token.
+    pub per_address: Option<u32>,
+    pub max_supply: Option<u64>,
+}
+
+impl MintLimits {
     pub fn validate(&self) -> Result<(), ContractError> {
-        match &self.mint_price {
-            Some(coin) if coin.amount.le(&Uint128::zero()) => Err(ContractError::ZeroPrice),
-            None | Some(_) => Ok(()),
+        if self.per_address == Some(0) || self.max_supply == Some(0) {
+            return Err(ContractError::ZeroMintLimit);
+        }
+        Ok(())
+    }
+}
+
+/// Rules that the token ids of mints have to follow.
+#[cw_serde]
+#[derive(Default)]
+pub struct TokenIdPolicy {
+    /// Any character is allowed when unset.
+    pub charset: Option<TokenIdCharset>,
+    pub min_length: Option<u32>,
+    pub max_length: Option<u32>,
+    /// Lowercases token ids before they are checked and forwarded.
+    #[serde(default)]
+    pub force_lowercase: bool,
+    /// Token ids that cannot be minted.
+    #[serde(default)]
+    pub reserved: Vec<String>,
+}
+
+#[cw_serde]
+pub enum TokenIdCharset {
+    /// ASCII letters and digits.
+    Alphanumeric,
+    /// Lowercase ASCII letters, digits and hyphens.
+    Hostname,
+    Custom {
+        chars: String,
+    },
+}
+
+impl TokenIdCharset {
+    pub fn allows(&self, character: char) -> bool {
+        match self {
+            TokenIdCharset::Alphanumeric => character.is_ascii_alphanumeric(),
+            TokenIdCharset::Hostname => {
+                character.is_ascii_lowercase() || character.is_ascii_digit() || character == '-'
+            }
+            TokenIdCharset::Custom { chars } => chars.contains(character),
+        }
+    }
+}
+
+impl TokenIdPolicy {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        match (self.min_length, self.max_length) {
+            (Some(min_length), Some(max_length)) if max_length < min_length => {
+                Err(ContractError::InvalidTokenIdLengths {
+                    min_length,
+                    max_length,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the token id as it is to be minted, or why it cannot be.
+    pub fn normalize(&self, token_id: &str) -> Result<String, ContractError> {
+        let token_id = if self.force_lowercase {
+            token_id.to_lowercase()
+        } else {
+            token_id.to_owned()
+        };
+        let length = token_id.chars().count();
+        if let Some(min_length) = self.min_length {
+            if length < min_length as usize {
+                return Err(ContractError::TokenIdTooShort {
+                    token_id,
+                    min_length,
+                });
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            if (max_length as usize) < length {
+                return Err(ContractError::TokenIdTooLong {
+                    token_id,
+                    max_length,
+                });
+            }
         }
+        if let Some(charset) = &self.charset {
+            if let Some(character) = token_id
+                .chars()
+                .find(|character| !charset.allows(*character))
+            {
+                return Err(ContractError::TokenIdInvalidChar {
+                    token_id,
+                    character,
+                });
+            }
+        }
+        let is_reserved = self.reserved.iter().any(|reserved| {
+            if self.force_lowercase {
+                reserved.to_lowercase() == token_id
+            } else {
+                reserved == &token_id
+            }
+        });
+        if is_reserved {
+            return Err(ContractError::TokenIdReserved { token_id });
+        }
+        Ok(token_id)
     }
 }
 
+#[cw_serde]
+pub struct CollectionInfo {
+    pub label: String,
+    pub code_id: u64,
+    pub created_at: Timestamp,
+}
+
+pub type CollectionInstantiateMsg = Cw721InstantiateMsg<Option<Empty>>;
 pub type CollectionExecuteMsg = Cw721ExecuteMsg<Option<Empty>, Option<Empty>, Empty>;
+
+/// The kinds of collection messages, one for each variant of `CollectionExecuteMsg`.
+#[cw_serde]
+pub enum CollectionAction {
+    Mint,
+    Burn,
+    TransferNft,
+    SendNft,
+    Approve,
+    Revoke,
+    ApproveAll,
+    RevokeAll,
+    UpdateOwnership,
+    UpdateMinterOwnership,
+    UpdateCreatorOwnership,
+    UpdateCollectionInfo,
+    UpdateExtension,
+    UpdateNftInfo,
+    SetWithdrawAddress,
+    RemoveWithdrawAddress,
+    WithdrawFunds,
+}
+
+impl CollectionAction {
+    pub const ALL: [CollectionAction; 17] = [
+        CollectionAction::Mint,
+        CollectionAction::Burn,
+        CollectionAction::TransferNft,
+        CollectionAction::SendNft,
+        CollectionAction::Approve,
+        CollectionAction::Revoke,
+        CollectionAction::ApproveAll,
+        CollectionAction::RevokeAll,
+        CollectionAction::UpdateOwnership,
+        CollectionAction::UpdateMinterOwnership,
+        CollectionAction::UpdateCreatorOwnership,
+        CollectionAction::UpdateCollectionInfo,
+        CollectionAction::UpdateExtension,
+        CollectionAction::UpdateNftInfo,
+        CollectionAction::SetWithdrawAddress,
+        CollectionAction::RemoveWithdrawAddress,
+        CollectionAction::WithdrawFunds,
+    ];
+
+    #[allow(deprecated)]
+    pub fn of(message: &CollectionExecuteMsg) -> Self {
+        match message {
+            CollectionExecuteMsg::Mint { .. } => CollectionAction::Mint,
+            CollectionExecuteMsg::Burn { .. } => CollectionAction::Burn,
+            CollectionExecuteMsg::TransferNft { .. } => CollectionAction::TransferNft,
+            CollectionExecuteMsg::SendNft { .. } => CollectionAction::SendNft,
+            CollectionExecuteMsg::Approve { .. } => CollectionAction::Approve,
+            CollectionExecuteMsg::Revoke { .. } => CollectionAction::Revoke,
+            CollectionExecuteMsg::ApproveAll { .. } => CollectionAction::ApproveAll,
+            CollectionExecuteMsg::RevokeAll { .. } => CollectionAction::RevokeAll,
+            CollectionExecuteMsg::UpdateOwnership(_) => CollectionAction::UpdateOwnership,
+            CollectionExecuteMsg::UpdateMinterOwnership(_) => {
+                CollectionAction::UpdateMinterOwnership
+            }
+            CollectionExecuteMsg::UpdateCreatorOwnership(_) => {
+                CollectionAction::UpdateCreatorOwnership
+            }
+            CollectionExecuteMsg::UpdateCollectionInfo { .. } => {
+                CollectionAction::UpdateCollectionInfo
+            }
+            CollectionExecuteMsg::UpdateExtension { .. } => CollectionAction::UpdateExtension,
+            CollectionExecuteMsg::UpdateNftInfo { .. } => CollectionAction::UpdateNftInfo,
+            CollectionExecuteMsg::SetWithdrawAddress { .. } => CollectionAction::SetWithdrawAddress,
+            CollectionExecuteMsg::RemoveWithdrawAddress {} => {
+                CollectionAction::RemoveWithdrawAddress
+            }
+            CollectionExecuteMsg::WithdrawFunds { .. } => CollectionAction::WithdrawFunds,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CollectionAction::Mint => "mint",
+            CollectionAction::Burn => "burn",
+            CollectionAction::TransferNft => "transfer_nft",
+            CollectionAction::SendNft => "send_nft",
+            CollectionAction::Approve => "approve",
+            CollectionAction::Revoke => "revoke",
+            CollectionAction::ApproveAll => "approve_all",
+            CollectionAction::RevokeAll => "revoke_all",
+            CollectionAction::UpdateOwnership => "update_ownership",
+            CollectionAction::UpdateMinterOwnership => "update_minter_ownership",
+            CollectionAction::UpdateCreatorOwnership => "update_creator_ownership",
+            CollectionAction::UpdateCollectionInfo => "update_collection_info",
+            CollectionAction::UpdateExtension => "update_extension",
+            CollectionAction::UpdateNftInfo => "update_nft_info",
+            CollectionAction::SetWithdrawAddress => "set_withdraw_address",
+            CollectionAction::RemoveWithdrawAddress => "remove_withdraw_address",
+            CollectionAction::WithdrawFunds => "withdraw_funds",
+        }
+    }
+
+    /// Mints and token-level actions are public. Operator approvals, which the manager would give
+    /// for its own tokens, and the actions it can only take as minter or creator of the
+    /// collection are reserved to its owner.
+    pub fn default_access(&self) -> ActionAccess {
+        match self {
+            CollectionAction::Mint
+            | CollectionAction::Burn
+            | CollectionAction::TransferNft
+            | CollectionAction::SendNft
+            | CollectionAction::Approve
+            | CollectionAction::Revoke => ActionAccess::Public,
+            _ => ActionAccess::OwnerOnly,
+        }
+    }
+}
+
+/// Who can have the manager pass an action on to a collection.
+#[cw_serde]
+pub enum ActionAccess {
+    Public,
+    OwnerOnly,
+    Forbidden,
+}
+
+/// Overrides the default access to the actions it has rules for.
+#[cw_serde]
+#[derive(Default)]
+pub struct PassThroughPolicy {
+    pub rules: Vec<ActionRule>,
+}
+
+#[cw_serde]
+pub struct ActionRule {
+    pub action: CollectionAction,
+    pub access: ActionAccess,
+}
+
+impl PassThroughPolicy {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if self.rules[..index]
+                .iter()
+                .any(|other| other.action == rule.action)
+            {
+                return Err(ContractError::DuplicateActionRule {
+                    action: rule.action.as_str().to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn access(&self, action: &CollectionAction) -> ActionAccess {
+        self.rules
+            .iter()
+            .find(|rule| &rule.action == action)
+            .map_or(action.default_access(), |rule| rule.access.to_owned())
+    }
+}
+
+/// Stops collection messages, all of them or just those of one collection, except the allowed
+/// actions.
+#[cw_serde]
+pub struct PauseState {
+    #[serde(default)]
+    pub allowed_actions: Vec<CollectionAction>,
+}
+
 pub type CollectionQueryMsg = Cw721QueryMsg<Option<Empty>, Option<Empty>, Empty>;
 
 #[cw_serde]
@@ -32,6 +902,221 @@ pub enum ExecuteMsg {
     PassThrough {
         collection: String,
         message: CollectionExecuteMsg,
+        #[serde(default)]
+        mint_options: Option<MintOptions>,
+    },
+    BatchPassThrough {
+        items: Vec<PassThroughItem>,
+        #[serde(default)]
+        mode: BatchMode,
+    },
+    SetCollectionPaymentParams {
+        collection: String,
+        payment_params: PaymentParams,
+    },
+    RemoveCollectionPaymentParams {
+        collection: String,
+    },
+    AddCollection {
+        collection: String,
+        label: String,
+    },
+    RemoveCollection {
+        collection: String,
+    },
+    UpdateConfig(Config),
+    /// Replaces the mint phases of the collection, an empty list lifts the restrictions. Mints
+    /// outside every phase window go by the payment params.
+    SetMintPhases {
+        collection: String,
+        phases: Vec<MintPhase>,
+    },
+    /// Unset limits lift the corresponding cap.
+    SetMintLimits {
+        collection: String,
+        limits: MintLimits,
+    },
+    /// The default policy lets any token id through.
+    SetTokenIdPolicy {
+        collection: String,
+        policy: TokenIdPolicy,
+    },
+    SetPassThroughPolicy {
+        collection: String,
+        policy: PassThroughPolicy,
+    },
+    /// Hides a mint until it is revealed, see `mint_commitment`.
+    CommitMint {
+        commitment: HexBinary,
+    },
+    /// Passes the mint of an earlier commit through.
+    RevealMint {
+        collection: String,
+        message: CollectionExecuteMsg,
+        salt: HexBinary,
+        #[serde(default)]
+        mint_options: Option<MintOptions>,
+    },
+    /// Removes commits that can no longer be revealed and settles their deposits.
+    ClearExpiredCommitments {
+        limit: Option<u32>,
+    },
+    /// Extends the registration of a token id that has not expired yet, at the yearly price.
+    Renew {
+        collection: String,
+        token_id: String,
+        years: u32,
+    },
+    /// Drops an expired registration and burns its token, and mints it again when it is
+    /// reassigned. The burn is best-effort: cw721 only lets the manager burn as an operator of the
+    /// holder, so when it fails the token stays with its holder and nothing is reassigned.
+    Reclaim {
+        collection: String,
+        token_id: String,
+        reassign: Option<Reassignment>,
+    },
+    /// Auctions the mint of a token id, which cannot be minted otherwise until it is settled.
+    OpenAuction {
+        collection: String,
+        token_id: String,
+        reserve_price: Coin,
+        end: Timestamp,
+        min_increment: Uint128,
+    },
+    /// Escrows the funds sent, which include the registration price of the auction, and refunds
+    /// the bid it outbids.
+    Bid {
+        auction_id: u64,
+    },
+    /// Mints the token to the highest bidder once the auction ended, and pays out the bid. The bid
+    /// is refunded instead when the mint cannot happen, such as when the collection reached its
+    /// max supply in the meantime. Whether the token gets registered is settled when it opens.
+    SettleAuction {
+        auction_id: u64,
+    },
+    /// Pauses one collection, or all of them when `collection` is unset. Owner or guardian only.
+    Pause {
+        collection: Option<String>,
+        #[serde(default)]
+        allowed_actions: Vec<CollectionAction>,
+    },
+    /// Owner or guardian only.
+    Unpause {
+        collection: Option<String>,
+    },
+    SetGuardian {
+        guardian: Option<String>,
+    },
+    /// Sends revenue credited to the sender, in all its denoms when `denoms` is empty. Withdraws
+    /// `amount` of each denom when set, and everything otherwise.
+    Withdraw {
+        denoms: Vec<String>,
+        amount: Option<Uint128>,
+    },
+    /// Adds the funds sent to the pool that burn rebates on the collection are paid from.
+    FundRebatePool {
+        collection: String,
+    },
+    /// Sends `amount` out of the rebate pool of the collection to the owner.
+    WithdrawRebatePool {
+        collection: String,
+        amount: Coin,
+    },
+    /// Refunds what a minter paid above the clearing price of each dutch auction whose price
+    /// stopped declining, and pays the rest of the held payments out.
+    SettleDutchEscrow {
+        collection: String,
+        minter: String,
+    },
+    CreateCollection {
+        name: String,
+        symbol: String,
+        label: String,
+    },
+    UpdatePaymentParams(PaymentParams),
+    Receive(Cw20ReceiveMsg),
+    AddAcceptedCw20 {
+        token: String,
+    },
+    RemoveAcceptedCw20 {
+        token: String,
+    },
+    ProposeOwner {
+        new_owner: String,
+    },
+    AcceptOwnership,
+    RenounceOwnership,
+}
+
+/// An English auction for minting a token id to the highest bidder.
+#[cw_serde]
+pub struct Auction {
+    pub id: u64,
+    pub collection: Addr,
+    pub token_id: String,
+    /// The lowest first bid, in the denom of the auction.
+    pub reserve_price: Coin,
+    /// Bids are taken until then.
+    pub end: Timestamp,
+    /// How much each bid has to add to the highest one.
+    pub min_increment: Uint128,
+    /// The first year of registration, which bids pay on top of the amount bid.
+    #[serde(default)]
+    pub registration_price: Option<Uint128>,
+    pub highest_bid: Option<Bid>,
+    pub bid_count: u32,
+}
+
+#[cw_serde]
+pub struct Bid {
+    pub bidder: Addr,
+    pub amount: Uint128,
+    pub placed_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct Reassignment {
+    pub owner: String,
+    pub years: u32,
+}
+
+#[cw_serde]
+pub struct PassThroughItem {
+    pub collection: String,
+    pub message: CollectionExecuteMsg,
+    #[serde(default)]
+    pub mint_options: Option<MintOptions>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MintOptions {
+    /// Proves the minter is in the merkle tree of the active mint phase.
+    #[serde(default)]
+    pub merkle_proof: Vec<HexBinary>,
+    /// Years to register the token id for, one when unset. Only collections priced by the year
+    /// register token ids.
+    #[serde(default)]
+    pub years: Option<u32>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum BatchMode {
+    /// Any failing item reverts the whole batch.
+    #[default]
+    Atomic,
+    /// Failing items are reported in events and their mint payment refunded.
+    BestEffort,
+}
+
+#[cw_serde]
+pub enum ReceiveMsg {
+    PassThrough {
+        collection: String,
+        message: CollectionExecuteMsg,
+        #[serde(default)]
+        mint_options: Option<MintOptions>,
     },
 }
 
@@ -45,6 +1130,111 @@ pub struct NameServiceExecuteMsgResponse {
 pub enum QueryMsg {
     #[returns(GetPaymentParamsResponse)]
     GetPaymentParams,
+    #[returns(GetConfigResponse)]
+    GetConfig,
+    #[returns(GetOwnershipResponse)]
+    GetOwnership,
+    #[returns(GetPaymentParamsResponse)]
+    GetCollectionPaymentParams { collection: String },
+    #[returns(ListCollectionPaymentParamsResponse)]
+    ListCollectionPaymentParams {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(ListCollectionsResponse)]
+    ListCollections {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(SimulateMintResponse)]
+    SimulateMint {
+        collection: String,
+        sender: String,
+        funds: Vec<Coin>,
+        /// Picks the length price, the other prices apply when unset.
+        #[serde(default)]
+        token_id: Option<String>,
+        /// Registration years, one when unset.
+        #[serde(default)]
+        years: Option<u32>,
+    },
+    /// Quotes the fee of passing a non-mint action through, in the same terms as a mint.
+    #[returns(SimulateMintResponse)]
+    SimulateAction {
+        collection: String,
+        sender: String,
+        funds: Vec<Coin>,
+        action: CollectionAction,
+    },
+    #[returns(ListAcceptedCw20Response)]
+    ListAcceptedCw20 {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(ActiveMintPhaseResponse)]
+    ActiveMintPhase { collection: String },
+    /// Only counts mints, whether the address is on the allowlist takes a proof to tell.
+    #[returns(MintAllowanceResponse)]
+    MintAllowance { collection: String, address: String },
+    #[returns(MintQuotaResponse)]
+    MintQuota { collection: String, address: String },
+    /// The access to every action of the collection, with the policy applied.
+    #[returns(PassThroughPolicyResponse)]
+    PassThroughPolicy { collection: String },
+    #[returns(ValidateTokenIdResponse)]
+    ValidateTokenId {
+        collection: String,
+        token_id: String,
+    },
+    #[returns(MintCommitmentResponse)]
+    MintCommitment { commitment: HexBinary },
+    #[returns(ExpiryResponse)]
+    Expiry {
+        collection: String,
+        token_id: String,
+    },
+    /// Registrations expiring from `start` until before `end`, soonest first.
+    #[returns(ExpiringTokensResponse)]
+    ExpiringTokens {
+        start: Timestamp,
+        end: Timestamp,
+        start_after: Option<Registration>,
+        limit: Option<u32>,
+    },
+    /// Auctions that have not been settled, including those past their end.
+    #[returns(ActiveAuctionsResponse)]
+    ActiveAuctions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Bids in the order they were placed, `start_after` counts them from 0.
+    #[returns(BidHistoryResponse)]
+    BidHistory {
+        auction_id: u64,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    /// The native prices of a mint at the current block time, for `token_id` if given.
+    #[returns(CurrentMintPriceResponse)]
+    CurrentMintPrice {
+        collection: String,
+        token_id: Option<String>,
+    },
+    /// The mint prices of the next `count` tokens of the collection, at most 30.
+    #[returns(NextMintPricesResponse)]
+    NextMintPrices { collection: String, count: u32 },
+    /// The global pause, and the pause of `collection` when given.
+    #[returns(PauseStatusResponse)]
+    PauseStatus { collection: Option<String> },
+    /// Revenue credited to `address` that it can withdraw.
+    #[returns(ClaimableResponse)]
+    Claimable { address: String },
+    /// Native revenue the collection earned, whether paid out or accrued.
+    #[returns(CollectionRevenueResponse)]
+    CollectionRevenue { collection: String },
+    /// What is left in the pool that pays the burn rebates of the collection.
+    #[returns(RebatePoolResponse)]
+    RebatePool { collection: String },
 }
 
 #[cw_serde]
@@ -52,12 +1242,233 @@ pub struct GetPaymentParamsResponse {
     pub payment_params: PaymentParams,
 }
 
+#[cw_serde]
+pub struct GetConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct GetOwnershipResponse {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct CollectionPaymentParams {
+    pub collection: Addr,
+    pub payment_params: PaymentParams,
+}
+
+#[cw_serde]
+pub struct ListCollectionPaymentParamsResponse {
+    pub collections: Vec<CollectionPaymentParams>,
+}
+
+#[cw_serde]
+pub struct ManagedCollection {
+    pub collection: Addr,
+    pub collection_info: CollectionInfo,
+}
+
+#[cw_serde]
+pub struct ListCollectionsResponse {
+    pub collections: Vec<ManagedCollection>,
+}
+
+#[cw_serde]
+pub struct MintPayout {
+    pub recipient: Addr,
+    pub amount: Coin,
+}
+
+#[cw_serde]
+pub struct SimulateMintResponse {
+    pub payment: Option<Coin>,
+    pub payouts: Vec<MintPayout>,
+    pub refund: Vec<Coin>,
+    /// Paid out of the rebate pool of the collection, for actions with a rebate.
+    pub rebate: Vec<MintPayout>,
+    /// The error a mint with these funds would fail with, in which case the rest is empty.
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct ListAcceptedCw20Response {
+    pub tokens: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct ActiveMintPhaseResponse {
+    pub phase: Option<MintPhase>,
+}
+
+#[cw_serde]
+pub struct MintAllowanceResponse {
+    pub phase: Option<String>,
+    pub minted: u32,
+    /// Unset when the address can mint without limit.
+    pub remaining: Option<u32>,
+}
+
+#[cw_serde]
+pub struct MintQuotaResponse {
+    pub limits: MintLimits,
+    pub minted_by_address: u32,
+    pub total_minted: u64,
+    /// Unset when there is no limit per address.
+    pub remaining_for_address: Option<u32>,
+    /// Unset when there is no maximum supply.
+    pub remaining_supply: Option<u64>,
+}
+
+#[cw_serde]
+pub struct PassThroughPolicyResponse {
+    pub rules: Vec<ActionRule>,
+}
+
+#[cw_serde]
+pub struct ValidateTokenIdResponse {
+    /// The token id as it would be minted, unset when it breaks the policy.
+    pub token_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct MintCommitmentResponse {
+    pub commitment: Option<MintCommitment>,
+    /// Whether the commitment can be revealed at the current height.
+    pub revealable: bool,
+    pub expired: bool,
+}
+
+#[cw_serde]
+pub struct ExpiryResponse {
+    /// Unset for token ids that are not registered, which do not expire.
+    pub expires_at: Option<Timestamp>,
+    pub expired: bool,
+}
+
+#[cw_serde]
+pub struct ExpiringTokensResponse {
+    pub registrations: Vec<Registration>,
+}
+
+#[cw_serde]
+pub struct ActiveAuctionsResponse {
+    pub auctions: Vec<Auction>,
+}
+
+#[cw_serde]
+pub struct BidHistoryResponse {
+    pub bids: Vec<Bid>,
+}
+
+#[cw_serde]
+pub struct CurrentMintPriceResponse {
+    pub prices: Vec<Coin>,
+    /// The lowest price paid for a dutch auction mint that is held for refunds.
+    pub clearing_price: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct PauseStatusResponse {
+    pub global: Option<PauseState>,
+    pub collection: Option<PauseState>,
+    pub guardian: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub balances: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct CollectionRevenueResponse {
+    pub revenue: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct RebatePoolResponse {
+    pub funds: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct NextMintPricesResponse {
+    pub supply: u64,
+    pub prices: Vec<Coin>,
+    /// What minting all of them in one batch costs.
+    pub total: Option<Coin>,
+}
+
 #[cw_serde]
 pub enum SudoMsg {
     UpdatePaymentParams(PaymentParams),
+    SetCollectionPaymentParams {
+        collection: String,
+        payment_params: PaymentParams,
+    },
+    RemoveCollectionPaymentParams {
+        collection: String,
+    },
+    AddCollection {
+        collection: String,
+        label: String,
+    },
+    RemoveCollection {
+        collection: String,
+    },
+    UpdateConfig(Config),
+    SetMintPhases {
+        collection: String,
+        phases: Vec<MintPhase>,
+    },
+    SetMintLimits {
+        collection: String,
+        limits: MintLimits,
+    },
+    SetTokenIdPolicy {
+        collection: String,
+        policy: TokenIdPolicy,
+    },
+    SetPassThroughPolicy {
+        collection: String,
+        policy: PassThroughPolicy,
+    },
+    AddAcceptedCw20 {
+        token: String,
+    },
+    RemoveAcceptedCw20 {
+        token: String,
+    },
+    SetOwner {
+        owner: Option<String>,
+    },
+    Pause {
+        collection: Option<String>,
+        #[serde(default)]
+        allowed_actions: Vec<CollectionAction>,
+    },
+    Unpause {
+        collection: Option<String>,
+    },
+    SetGuardian {
+        guardian: Option<String>,
+    },
 }
 
 #[cw_serde]
 pub struct MigrateMsg {
     pub payment_params: PaymentParams,
+    /// Required when the instance has no owner yet, as 0.1.0 ones do.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Collections created before the registry existed.
+    #[serde(default)]
+    pub collections: Vec<MigrateCollection>,
+}
+
+#[cw_serde]
+pub struct MigrateCollection {
+    pub collection: String,
+    pub label: String,
 }
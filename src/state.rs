@@ -1,8 +1,109 @@
-use cw_storage_plus::Item;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Empty, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
 
-use crate::msg::PaymentParams;
+use crate::msg::{
+    Auction, Bid, CollectionInfo, Config, MintCommitment, MintLimits, MintPayout, MintPhase,
+    PassThroughPolicy, PauseState, PaymentParams, Registration, TokenIdPolicy,
+};
 
 pub const CONTRACT_NAME: &str = "my-collection-manager";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const PAYMENT_PARAMS: Item<PaymentParams> = Item::new("payment_params");
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const OWNER: Item<Addr> = Item::new("owner");
+pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
+/// Can pause and unpause collection messages besides the owner.
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+pub const GLOBAL_PAUSE: Item<PauseState> = Item::new("global_pause");
+pub const COLLECTION_PAUSES: Map<&Addr, PauseState> = Map::new("collection_pauses");
+pub const COLLECTION_PAYMENT_PARAMS: Map<&Addr, PaymentParams> =
+    Map::new("collection_payment_params");
+pub const COLLECTIONS: Map<&Addr, CollectionInfo> = Map::new("collections");
+pub const ACCEPTED_CW20: Map<&Addr, Empty> = Map::new("accepted_cw20");
+pub const MINT_PHASES: Map<&Addr, Vec<MintPhase>> = Map::new("mint_phases");
+pub const PHASE_MINT_COUNTS: Map<(&Addr, &str, &Addr), u32> = Map::new("phase_mint_counts");
+pub const MINT_LIMITS: Map<&Addr, MintLimits> = Map::new("mint_limits");
+pub const COLLECTION_MINT_COUNTS: Map<&Addr, u64> = Map::new("collection_mint_counts");
+pub const ADDRESS_MINT_COUNTS: Map<(&Addr, &Addr), u32> = Map::new("address_mint_counts");
+pub const TOKEN_ID_POLICIES: Map<&Addr, TokenIdPolicy> = Map::new("token_id_policies");
+pub const PASS_THROUGH_POLICIES: Map<&Addr, PassThroughPolicy> = Map::new("pass_through_policies");
+pub const MINT_COMMITMENTS: Map<&[u8], MintCommitment> = Map::new("mint_commitments");
+/// Commitments by the height they were made at, so the expired ones can be found in order.
+pub const COMMITMENTS_BY_HEIGHT: Map<(u64, &[u8]), Empty> = Map::new("commitments_by_height");
+pub const REGISTRATIONS: Map<(&Addr, &str), Timestamp> = Map::new("registrations");
+/// Registrations by the seconds of their expiry, so the ones expiring in a window can be found.
+pub const REGISTRATION_EXPIRIES: Map<(u64, &Addr, &str), Empty> = Map::new("registration_expiries");
+pub const AUCTION_SEQUENCE: Item<u64> = Item::new("auction_sequence");
+pub const AUCTIONS: Map<u64, Auction> = Map::new("auctions");
+/// The open auction of each auctioned token id.
+pub const AUCTIONED_TOKENS: Map<(&Addr, &str), u64> = Map::new("auctioned_tokens");
+pub const AUCTION_BIDS: Map<(u64, u32), Bid> = Map::new("auction_bids");
+/// Keyed by collection, minter and start of the dutch auction in seconds.
+pub const DUTCH_ESCROWS: Map<(&Addr, &Addr, u64), DutchEscrow> = Map::new("dutch_escrows");
+/// The lowest price a dutch auction mint with a held payment was paid at, by collection and start
+/// of the dutch auction in seconds.
+pub const DUTCH_CLEARING_PRICES: Map<(&Addr, u64), Uint128> = Map::new("dutch_clearing_prices");
+/// Revenue credited to each payee per denom while revenue accrues in the manager.
+pub const CLAIMABLE_BALANCES: Map<(&Addr, &str), Uint128> = Map::new("claimable_balances");
+/// Native revenue of each collection per denom.
+pub const COLLECTION_REVENUE: Map<(&Addr, &str), Uint128> = Map::new("collection_revenue");
+/// Funds the owner put up for the burn rebates of each collection, per denom.
+pub const REBATE_POOLS: Map<(&Addr, &str), Uint128> = Map::new("rebate_pools");
+pub const REPLY_SEQUENCE: Item<u64> = Item::new("reply_sequence");
+pub const REPLY_CONTEXTS: Map<u64, ReplyContext> = Map::new("reply_contexts");
+
+/// What `reply` needs to know about the submessage it answers, saved under its reply id.
+#[cw_serde]
+pub enum ReplyContext {
+    PassThrough(PassThroughContext),
+    BatchItem {
+        index: u32,
+        pass_through: PassThroughContext,
+    },
+    CreateCollection(CollectionInfo),
+    Reclaim {
+        registration: Registration,
+        /// Gets the token minted once the burn succeeds.
+        reassign_to: Option<Addr>,
+    },
+}
+
+#[cw_serde]
+pub struct PassThroughContext {
+    pub sender: Addr,
+    pub collection: Addr,
+    pub token_id: Option<String>,
+    pub payment: Option<Coin>,
+    /// Set for mints, whose counts are given back when they fail.
+    pub counted_mint: Option<CountedMint>,
+    /// Payouts held back until the collection call succeeds.
+    pub held_payouts: Vec<MintPayout>,
+    /// Rebate payouts drawn from the rebate pool, paid once the collection call succeeds and put
+    /// back otherwise.
+    #[serde(default)]
+    pub held_rebate: Vec<MintPayout>,
+}
+
+#[cw_serde]
+pub struct CountedMint {
+    /// The mint phase the mint counted against.
+    pub phase: Option<String>,
+    /// The registration the mint recorded for its token id.
+    pub registration: Option<Registration>,
+    /// The part of a dutch auction payment the mint added to the escrow of the minter.
+    pub dutch_escrow: Option<DutchEscrow>,
+}
+
+/// Dutch auction payments of a minter, of which the part above the end price is held.
+#[cw_serde]
+pub struct DutchEscrow {
+    /// Tells the dutch auction apart from later ones on the collection.
+    pub start: Timestamp,
+    pub ends_at: Timestamp,
+    pub denom: String,
+    pub minted: u32,
+    pub paid: Uint128,
+    pub held: Uint128,
+}
@@ -7,8 +7,12 @@ use cw_multi_test::{App, AppBuilder, ContractWrapper, Executor, WasmSudo};
 use cw_my_collection_manager::{
     contract::{execute, instantiate, migrate, query, reply, sudo},
     msg::{
-        ExecuteMsg, GetPaymentParamsResponse, InstantiateMsg, MigrateMsg, PaymentParams, QueryMsg,
-        SudoMsg,
+        ActiveAuctionsResponse, BatchMode, Bid, BidHistoryResponse, CollectionInfo,
+        CollectionPaymentParams, Config, ExecuteMsg, ExpiryResponse, GetOwnershipResponse,
+        GetPaymentParamsResponse, InstantiateMsg, LengthPrice, ListCollectionPaymentParamsResponse,
+        ListCollectionsResponse, ManagedCollection, MigrateMsg, MintFailurePolicy, MintPayout,
+        PassThroughItem, PaymentParams, QueryMsg, Reassignment, RevenueShare, SimulateMintResponse,
+        SudoMsg, SECONDS_PER_YEAR,
     },
 };
 use cw_my_nameservice::{
@@ -68,7 +72,11 @@ fn instantiate_collection_manager(
             .instantiate_contract(
                 manager_code_id,
                 Addr::unchecked("deployer-manager"),
-                &InstantiateMsg { payment_params },
+                &InstantiateMsg {
+                    owner: Some("admin".to_owned()),
+                    payment_params,
+                    config: Config::default(),
+                },
                 &[],
                 "my-collection-manager",
                 None,
@@ -77,6 +85,18 @@ fn instantiate_collection_manager(
     )
 }
 
+fn add_collection(mock_app: &mut App, addr_manager: &Addr, addr_collection: &Addr) {
+    let add_sudo_msg = SudoMsg::AddCollection {
+        collection: addr_collection.to_string(),
+        label: "nameservice".to_owned(),
+    };
+    mock_app
+        .sudo(cw_multi_test::SudoMsg::Wasm(
+            WasmSudo::new(addr_manager, &add_sudo_msg).expect("Failed to serialize sudo message"),
+        ))
+        .expect("Failed to add collection");
+}
+
 #[test]
 fn test_mint_through() {
     // Arrange
@@ -84,12 +104,10 @@ fn test_mint_through() {
     let beneficiary_addr = Addr::unchecked("beneficiary");
     let (_, addr_manager) = instantiate_collection_manager(
         &mut mock_app,
-        PaymentParams {
-            beneficiary: beneficiary_addr.to_owned(),
-            mint_price: None,
-        },
+        PaymentParams::new(beneficiary_addr.to_owned(), None),
     );
     let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
     let owner_addr = Addr::unchecked("owner");
     let name_alice = "alice".to_owned();
     let sender_addr = Addr::unchecked("sender");
@@ -101,6 +119,7 @@ fn test_mint_through() {
             token_uri: None,
             extension: None,
         },
+        mint_options: None,
     };
 
     // Act
@@ -172,12 +191,10 @@ fn test_paid_mint_through() {
     let beneficiary = Addr::unchecked("beneficiary");
     let (_, addr_manager) = instantiate_collection_manager(
         &mut mock_app,
-        PaymentParams {
-            beneficiary: beneficiary.to_owned(),
-            mint_price: Some(minting_price.to_owned()),
-        },
+        PaymentParams::new(beneficiary.to_owned(), Some(minting_price.to_owned())),
     );
     let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
     let owner_addr = Addr::unchecked("owner");
     let name_alice = "alice".to_owned();
     let register_msg = ExecuteMsg::PassThrough {
@@ -188,6 +205,7 @@ fn test_paid_mint_through() {
             token_uri: None,
             extension: None,
         },
+        mint_options: None,
     };
     let half_silver = Coin {
         amount: Uint128::from(30u16),
@@ -253,6 +271,351 @@ fn test_paid_mint_through() {
     );
 }
 
+#[test]
+fn test_paid_mint_through_revenue_split() {
+    // Arrange
+    let sender_addr = Addr::unchecked("sender");
+    let minting_price = Coin {
+        amount: Uint128::from(100u16),
+        denom: "silver".to_owned(),
+    };
+    let mut mock_app = AppBuilder::default().build(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender_addr, vec![minting_price.to_owned()])
+            .expect("Failed to init bank balances");
+    });
+    let creator_addr = Addr::unchecked("creator");
+    let treasury_addr = Addr::unchecked("treasury");
+    let community_addr = Addr::unchecked("community");
+    let payment_params = PaymentParams {
+        revenue_split: vec![
+            RevenueShare {
+                recipient: creator_addr.to_owned(),
+                weight: 2,
+            },
+            RevenueShare {
+                recipient: treasury_addr.to_owned(),
+                weight: 1,
+            },
+        ],
+        ..PaymentParams::new(community_addr.to_owned(), Some(minting_price.to_owned()))
+    };
+    let (_, addr_manager) =
+        instantiate_collection_manager(&mut mock_app, payment_params.to_owned());
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+    let register_msg = ExecuteMsg::PassThrough {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
+    };
+
+    // Act
+    let result = mock_app.execute_contract(
+        sender_addr.to_owned(),
+        addr_manager.to_owned(),
+        &register_msg,
+        &[minting_price],
+    );
+
+    // Assert
+    assert!(result.is_ok(), "Failed to pass through the message");
+    for (recipient, expected_amount) in [
+        (creator_addr, 66u16),
+        (treasury_addr, 33u16),
+        (community_addr, 1u16),
+    ] {
+        assert_eq!(
+            vec![Coin {
+                amount: Uint128::from(expected_amount),
+                denom: "silver".to_owned(),
+            }],
+            mock_app
+                .wrap()
+                .query_all_balances(recipient)
+                .expect("Failed to get recipient balances")
+        );
+    }
+    let result = mock_app
+        .wrap()
+        .query_wasm_smart::<GetPaymentParamsResponse>(&addr_manager, &QueryMsg::GetPaymentParams);
+    assert_eq!(
+        result.expect("Failed to query payment params"),
+        GetPaymentParamsResponse { payment_params }
+    );
+}
+
+#[test]
+fn test_simulate_mint_matches_paid_mint() {
+    // Arrange
+    let sender_addr = Addr::unchecked("sender");
+    let minting_price = Coin {
+        amount: Uint128::from(55u16),
+        denom: "silver".to_owned(),
+    };
+    let sent_funds = vec![Coin {
+        amount: Uint128::from(60u16),
+        denom: "silver".to_owned(),
+    }];
+    let mut mock_app = AppBuilder::default().build(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender_addr, sent_funds.to_owned())
+            .expect("Failed to init bank balances");
+    });
+    let beneficiary = Addr::unchecked("beneficiary");
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams::new(beneficiary.to_owned(), Some(minting_price.to_owned())),
+    );
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+    let simulation = mock_app
+        .wrap()
+        .query_wasm_smart::<SimulateMintResponse>(
+            &addr_manager,
+            &QueryMsg::SimulateMint {
+                collection: addr_collection.to_string(),
+                sender: sender_addr.to_string(),
+                funds: sent_funds.to_owned(),
+                token_id: None,
+                years: None,
+            },
+        )
+        .expect("Failed to simulate mint");
+    let register_msg = ExecuteMsg::PassThrough {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
+    };
+
+    // Act
+    let result = mock_app.execute_contract(
+        sender_addr.to_owned(),
+        addr_manager,
+        &register_msg,
+        &sent_funds,
+    );
+
+    // Assert
+    assert!(result.is_ok(), "Failed to pass through the message");
+    assert_eq!(simulation.error, None);
+    assert_eq!(simulation.payment, Some(minting_price.to_owned()));
+    assert_eq!(
+        simulation.payouts,
+        vec![MintPayout {
+            recipient: beneficiary.to_owned(),
+            amount: minting_price,
+        }]
+    );
+    for payout in simulation.payouts {
+        assert_eq!(
+            vec![payout.amount],
+            mock_app
+                .wrap()
+                .query_all_balances(payout.recipient)
+                .expect("Failed to get payout balances")
+        );
+    }
+    assert_eq!(
+        simulation.refund,
+        mock_app
+            .wrap()
+            .query_all_balances(sender_addr)
+            .expect("Failed to get sender balances")
+    );
+}
+
+#[test]
+fn test_best_effort_batch_mint() {
+    // Arrange
+    let sender_addr = Addr::unchecked("sender");
+    let minting_price = Coin {
+        amount: Uint128::from(10u16),
+        denom: "silver".to_owned(),
+    };
+    let sent_funds = Coin {
+        amount: Uint128::from(20u16),
+        denom: "silver".to_owned(),
+    };
+    let mut mock_app = AppBuilder::default().build(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender_addr, vec![sent_funds.to_owned()])
+            .expect("Failed to init bank balances");
+    });
+    let beneficiary = Addr::unchecked("beneficiary");
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams::new(beneficiary.to_owned(), Some(minting_price.to_owned())),
+    );
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+    let mint_item = PassThroughItem {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
+    };
+    let batch_msg = ExecuteMsg::BatchPassThrough {
+        items: vec![mint_item.to_owned(), mint_item],
+        mode: BatchMode::BestEffort,
+    };
+
+    // Act
+    let result = mock_app.execute_contract(
+        sender_addr.to_owned(),
+        addr_manager.to_owned(),
+        &batch_msg,
+        &[sent_funds],
+    );
+
+    // Assert
+    assert!(result.is_ok(), "Failed to pass through the batch");
+    let result = result.unwrap();
+    let expected_batch_event = Event::new("wasm-my-collection-manager")
+        .add_attribute("batch-size", "2")
+        .add_attribute("mint-payment", "20silver");
+    result.assert_event(&expected_batch_event);
+    let expected_success_event =
+        Event::new("wasm-my-collection-manager").add_attribute("batch-item-succeeded", "0");
+    result.assert_event(&expected_success_event);
+    assert!(result.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(
+            |attribute| attribute.key == "batch-item-failed" && attribute.value.starts_with("1:")
+        )));
+    for addr in [beneficiary, sender_addr] {
+        assert_eq!(
+            vec![minting_price.to_owned()],
+            mock_app
+                .wrap()
+                .query_all_balances(addr)
+                .expect("Failed to get balances")
+        );
+    }
+    assert_eq!(
+        Vec::<Coin>::new(),
+        mock_app
+            .wrap()
+            .query_all_balances(addr_manager)
+            .expect("Failed to get manager balances")
+    );
+}
+
+#[test]
+fn test_refund_failed_mint() {
+    // Arrange
+    let sender_addr = Addr::unchecked("sender");
+    let minting_price = Coin {
+        amount: Uint128::from(100u16),
+        denom: "silver".to_owned(),
+    };
+    let mut mock_app = AppBuilder::default().build(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &sender_addr,
+                vec![Coin {
+                    amount: Uint128::from(200u16),
+                    denom: "silver".to_owned(),
+                }],
+            )
+            .expect("Failed to init bank balances");
+    });
+    let beneficiary = Addr::unchecked("beneficiary");
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams::new(beneficiary.to_owned(), Some(minting_price.to_owned())),
+    );
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+    let update_sudo_msg = SudoMsg::UpdateConfig(Config {
+        collection_code_id: None,
+        max_batch_size: None,
+        mint_failure_policy: MintFailurePolicy::Refund { fee_bps: 500 },
+        accrue_revenue: false,
+        commit_reveal: None,
+    });
+    mock_app
+        .sudo(cw_multi_test::SudoMsg::Wasm(
+            WasmSudo::new(&addr_manager, &update_sudo_msg)
+                .expect("Failed to serialize sudo message"),
+        ))
+        .expect("Failed to update config");
+    let register_msg = ExecuteMsg::PassThrough {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
+    };
+    mock_app
+        .execute_contract(
+            sender_addr.to_owned(),
+            addr_manager.to_owned(),
+            &register_msg,
+            &[minting_price.to_owned()],
+        )
+        .expect("Failed to mint alice");
+
+    // Act
+    let result = mock_app.execute_contract(
+        sender_addr.to_owned(),
+        addr_manager.to_owned(),
+        &register_msg,
+        &[minting_price],
+    );
+
+    // Assert
+    assert!(result.is_ok(), "Failed mint should not revert");
+    let result = result.unwrap();
+    let expected_failure_event = Event::new("wasm-my-collection-manager")
+        .add_attribute("mint-failed", addr_collection.to_string())
+        .add_attribute("mint-fee", "5silver")
+        .add_attribute("mint-refund", "95silver");
+    result.assert_event(&expected_failure_event);
+    for (addr, expected_amount) in [(beneficiary, 105u16), (sender_addr, 95u16)] {
+        assert_eq!(
+            vec![Coin {
+                amount: Uint128::from(expected_amount),
+                denom: "silver".to_owned(),
+            }],
+            mock_app
+                .wrap()
+                .query_all_balances(addr)
+                .expect("Failed to get balances")
+        );
+    }
+    assert_eq!(
+        Vec::<Coin>::new(),
+        mock_app
+            .wrap()
+            .query_all_balances(addr_manager)
+            .expect("Failed to get manager balances")
+    );
+}
+
 #[test]
 fn test_mint_num_tokens() {
     // Arrange
@@ -260,12 +623,10 @@ fn test_mint_num_tokens() {
     let beneficiary_addr = Addr::unchecked("beneficiary");
     let (_, addr_manager) = instantiate_collection_manager(
         &mut mock_app,
-        PaymentParams {
-            beneficiary: beneficiary_addr.to_owned(),
-            mint_price: None,
-        },
+        PaymentParams::new(beneficiary_addr.to_owned(), None),
     );
     let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
     let owner_addr = Addr::unchecked("owner");
     let name_alice = "alice".to_owned();
     let name_bob = "bob".to_owned();
@@ -278,6 +639,7 @@ fn test_mint_num_tokens() {
             token_uri: None,
             extension: None,
         },
+        mint_options: None,
     };
     let _ = mock_app
         .execute_contract(
@@ -295,6 +657,7 @@ fn test_mint_num_tokens() {
             token_uri: None,
             extension: None,
         },
+        mint_options: None,
     };
 
     // Act
@@ -364,18 +727,15 @@ fn test_sudo_update_payment_params() {
     let beneficiary_addr = Addr::unchecked("beneficiary");
     let (_, addr_manager) = instantiate_collection_manager(
         &mut mock_app,
-        PaymentParams {
-            beneficiary: beneficiary_addr.to_owned(),
-            mint_price: None,
-        },
+        PaymentParams::new(beneficiary_addr.to_owned(), None),
     );
-    let new_payment_params = PaymentParams {
-        beneficiary: beneficiary_addr.to_owned(),
-        mint_price: Some(Coin {
+    let new_payment_params = PaymentParams::new(
+        beneficiary_addr.to_owned(),
+        Some(Coin {
             denom: "silver".to_owned(),
             amount: Uint128::from(23u16),
         }),
-    };
+    );
     let update_sudo_msg = SudoMsg::UpdatePaymentParams(new_payment_params.to_owned());
     let sudo_msg = cw_multi_test::SudoMsg::Wasm(
         WasmSudo::new(&addr_manager, &update_sudo_msg).expect("Failed to serialize sudo message"),
@@ -406,72 +766,383 @@ fn test_sudo_update_payment_params() {
 }
 
 #[test]
-fn test_migrate_payment_params() {
-    // Arrange old smart contract
-    #[cw_serde]
-    struct OldInstantiateMsg {}
+fn test_add_collection_and_reject_unregistered() {
+    // Arrange
     let mut mock_app = App::default();
-    let admin_addr = Addr::unchecked("admin");
-    let old_code = Box::new(
-        ContractWrapper::new(
-            execute,
-            |_: DepsMut, _: Env, _: MessageInfo, _: OldInstantiateMsg| -> Result<Response, Error> {
-                Ok(Response::default())
-            },
-            query,
-        )
-        .with_reply(reply)
-        .with_sudo(sudo),
-    );
-    let manager_old_code_id = mock_app.store_code(old_code);
-    let addr_manager = mock_app
-        .instantiate_contract(
-            manager_old_code_id,
-            Addr::unchecked("deployer-manager"),
-            &OldInstantiateMsg {},
-            &[],
-            "my-collection-manager",
-            Some(admin_addr.to_string()),
-        )
-        .expect("Failed to instantiate old collection manager");
-    // Arrange migration
-    let new_code = Box::new(
-        ContractWrapper::new(execute, instantiate, query)
-            .with_reply(reply)
-            .with_sudo(sudo)
-            .with_migrate(migrate),
-    );
-    let manager_new_code_id = mock_app.store_code(new_code);
     let beneficiary_addr = Addr::unchecked("beneficiary");
-    let new_payment_params = PaymentParams {
-        beneficiary: beneficiary_addr.to_owned(),
-        mint_price: Some(Coin {
-            denom: "silver".to_owned(),
-            amount: Uint128::from(23u16),
-        }),
-    };
-    let migrate_msg = MigrateMsg {
-        payment_params: new_payment_params.to_owned(),
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams::new(beneficiary_addr.to_owned(), None),
+    );
+    let (nameservice_code_id, addr_collection) =
+        instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    let register_msg = ExecuteMsg::PassThrough {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
     };
-
-    // Act
-    let result = mock_app.migrate_contract(
-        admin_addr,
+    let unregistered_result = mock_app.execute_contract(
+        Addr::unchecked("sender"),
         addr_manager.to_owned(),
-        &migrate_msg,
-        manager_new_code_id,
+        &register_msg,
+        &[],
+    );
+    assert!(
+        unregistered_result.is_err(),
+        "Should not pass through to an unregistered collection"
     );
 
+    // Act
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+
     // Assert
-    assert!(result.is_ok(), "Failed to migrate the contract");
-    let result = result.unwrap();
-    let expected_migrate_event = Event::new("migrate")
+    let result = mock_app.wrap().query_wasm_smart::<ListCollectionsResponse>(
+        &addr_manager,
+        &QueryMsg::ListCollections {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(result.is_ok(), "Failed to list collections");
+    assert_eq!(
+        result.unwrap(),
+        ListCollectionsResponse {
+            collections: vec![ManagedCollection {
+                collection: addr_collection.to_owned(),
+                collection_info: CollectionInfo {
+                    label: "nameservice".to_owned(),
+                    code_id: nameservice_code_id,
+                    created_at: mock_app.block_info().time,
+                },
+            }],
+        }
+    );
+    let result =
+        mock_app.execute_contract(Addr::unchecked("sender"), addr_manager, &register_msg, &[]);
+    assert!(
+        result.is_ok(),
+        "Failed to pass through to a registered collection"
+    );
+}
+
+#[test]
+fn test_create_collection() {
+    // Arrange
+    let mut mock_app = App::default();
+    let beneficiary_addr = Addr::unchecked("beneficiary");
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams::new(beneficiary_addr.to_owned(), None),
+    );
+    let (nameservice_code_id, _) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    let update_sudo_msg = SudoMsg::UpdateConfig(Config {
+        collection_code_id: Some(nameservice_code_id),
+        max_batch_size: None,
+        mint_failure_policy: MintFailurePolicy::Revert,
+        accrue_revenue: false,
+        commit_reveal: None,
+    });
+    mock_app
+        .sudo(cw_multi_test::SudoMsg::Wasm(
+            WasmSudo::new(&addr_manager, &update_sudo_msg)
+                .expect("Failed to serialize sudo message"),
+        ))
+        .expect("Failed to update config");
+    let create_msg = ExecuteMsg::CreateCollection {
+        name: "more names".to_owned(),
+        symbol: "MMN".to_owned(),
+        label: "more-nameservice".to_owned(),
+    };
+
+    // Act
+    let result = mock_app.execute_contract(
+        Addr::unchecked("admin"),
+        addr_manager.to_owned(),
+        &create_msg,
+        &[],
+    );
+
+    // Assert
+    assert!(result.is_ok(), "Failed to create collection");
+    let result = result.unwrap();
+    let addr_collection = Addr::unchecked("contract2");
+    let expected_create_event = Event::new("wasm-my-collection-manager")
+        .add_attribute("_contract_address", addr_manager.to_owned())
+        .add_attribute("create-collection", addr_collection.to_owned())
+        .add_attribute("create-collection-label", "more-nameservice")
+        .add_attribute("create-collection-code-id", nameservice_code_id.to_string());
+    result.assert_event(&expected_create_event);
+    let result = mock_app.wrap().query_wasm_smart::<ListCollectionsResponse>(
+        &addr_manager,
+        &QueryMsg::ListCollections {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(result.is_ok(), "Failed to list collections");
+    assert_eq!(
+        result.unwrap(),
+        ListCollectionsResponse {
+            collections: vec![ManagedCollection {
+                collection: addr_collection.to_owned(),
+                collection_info: CollectionInfo {
+                    label: "more-nameservice".to_owned(),
+                    code_id: nameservice_code_id,
+                    created_at: mock_app.block_info().time,
+                },
+            }],
+        }
+    );
+    let register_msg = ExecuteMsg::PassThrough {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: "alice".to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
+    };
+    let result =
+        mock_app.execute_contract(Addr::unchecked("sender"), addr_manager, &register_msg, &[]);
+    assert!(result.is_ok(), "Failed to mint on the created collection");
+}
+
+#[test]
+fn test_owner_set_collection_payment_params() {
+    // Arrange
+    let mut mock_app = App::default();
+    let admin_addr = Addr::unchecked("admin");
+    let beneficiary_addr = Addr::unchecked("beneficiary");
+    let code = Box::new(
+        ContractWrapper::new(execute, instantiate, query)
+            .with_reply(reply)
+            .with_sudo(sudo),
+    );
+    let manager_code_id = mock_app.store_code(code);
+    let addr_manager = mock_app
+        .instantiate_contract(
+            manager_code_id,
+            Addr::unchecked("deployer-manager"),
+            &InstantiateMsg {
+                owner: Some(admin_addr.to_string()),
+                payment_params: PaymentParams::new(beneficiary_addr.to_owned(), None),
+                config: Config::default(),
+            },
+            &[],
+            "my-collection-manager",
+            None,
+        )
+        .expect("Failed to instantiate collection manager");
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    let collection_payment_params = PaymentParams::new(
+        beneficiary_addr.to_owned(),
+        Some(Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(23u16),
+        }),
+    );
+    let set_msg = ExecuteMsg::SetCollectionPaymentParams {
+        collection: addr_collection.to_string(),
+        payment_params: collection_payment_params.to_owned(),
+    };
+    let unauthorized_result = mock_app.execute_contract(
+        Addr::unchecked("sender"),
+        addr_manager.to_owned(),
+        &set_msg,
+        &[],
+    );
+    assert!(
+        unauthorized_result.is_err(),
+        "Non-owner should not set collection payment params"
+    );
+
+    // Act
+    let result = mock_app.execute_contract(admin_addr, addr_manager.to_owned(), &set_msg, &[]);
+
+    // Assert
+    assert!(result.is_ok(), "Failed to set collection payment params");
+    let result = result.unwrap();
+    let expected_set_event = Event::new("wasm-my-collection-manager")
+        .add_attribute("_contract_address", addr_manager.to_owned())
+        .add_attribute(
+            "update-collection-payment-params",
+            addr_collection.to_owned(),
+        )
+        .add_attribute(
+            "update-payment-params-beneficiary",
+            beneficiary_addr.to_owned(),
+        )
+        .add_attribute("update-payment-params-mint-price-denom", "silver")
+        .add_attribute("update-payment-params-mint-price-amount", "23");
+    result.assert_event(&expected_set_event);
+    let result = mock_app
+        .wrap()
+        .query_wasm_smart::<ListCollectionPaymentParamsResponse>(
+            &addr_manager,
+            &QueryMsg::ListCollectionPaymentParams {
+                start_after: None,
+                limit: None,
+            },
+        );
+    assert!(result.is_ok(), "Failed to list collection payment params");
+    assert_eq!(
+        result.unwrap(),
+        ListCollectionPaymentParamsResponse {
+            collections: vec![CollectionPaymentParams {
+                collection: addr_collection.to_owned(),
+                payment_params: collection_payment_params.to_owned(),
+            }],
+        }
+    );
+    let result = mock_app
+        .wrap()
+        .query_wasm_smart::<GetPaymentParamsResponse>(
+            &addr_manager,
+            &QueryMsg::GetCollectionPaymentParams {
+                collection: addr_collection.to_string(),
+            },
+        );
+    assert!(result.is_ok(), "Failed to query collection payment params");
+    assert_eq!(
+        result.unwrap(),
+        GetPaymentParamsResponse {
+            payment_params: collection_payment_params
+        }
+    );
+}
+
+#[test]
+fn test_ownership_transfer() {
+    // Arrange
+    let mut mock_app = App::default();
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams::new(Addr::unchecked("beneficiary"), None),
+    );
+    let new_owner_addr = Addr::unchecked("new-owner");
+    mock_app
+        .execute_contract(
+            Addr::unchecked("admin"),
+            addr_manager.to_owned(),
+            &ExecuteMsg::ProposeOwner {
+                new_owner: new_owner_addr.to_string(),
+            },
+            &[],
+        )
+        .expect("Failed to propose owner");
+    let result = mock_app
+        .wrap()
+        .query_wasm_smart::<GetOwnershipResponse>(&addr_manager, &QueryMsg::GetOwnership);
+    assert_eq!(
+        result.expect("Failed to query ownership"),
+        GetOwnershipResponse {
+            owner: Some(Addr::unchecked("admin")),
+            pending_owner: Some(new_owner_addr.to_owned()),
+        }
+    );
+
+    // Act
+    let result = mock_app.execute_contract(
+        new_owner_addr.to_owned(),
+        addr_manager.to_owned(),
+        &ExecuteMsg::AcceptOwnership,
+        &[],
+    );
+
+    // Assert
+    assert!(result.is_ok(), "Failed to accept ownership");
+    let expected_owner_event = Event::new("wasm-my-collection-manager")
+        .add_attribute("_contract_address", addr_manager.to_owned())
+        .add_attribute("update-owner", new_owner_addr.to_owned());
+    result.unwrap().assert_event(&expected_owner_event);
+    let result = mock_app
+        .wrap()
+        .query_wasm_smart::<GetOwnershipResponse>(&addr_manager, &QueryMsg::GetOwnership);
+    assert_eq!(
+        result.expect("Failed to query ownership"),
+        GetOwnershipResponse {
+            owner: Some(new_owner_addr),
+            pending_owner: None,
+        }
+    );
+}
+
+#[test]
+fn test_migrate_payment_params() {
+    // Arrange old smart contract
+    #[cw_serde]
+    struct OldInstantiateMsg {}
+    let mut mock_app = App::default();
+    let admin_addr = Addr::unchecked("admin");
+    let old_code = Box::new(
+        ContractWrapper::new(
+            execute,
+            |_: DepsMut, _: Env, _: MessageInfo, _: OldInstantiateMsg| -> Result<Response, Error> {
+                Ok(Response::default())
+            },
+            query,
+        )
+        .with_reply(reply)
+        .with_sudo(sudo),
+    );
+    let manager_old_code_id = mock_app.store_code(old_code);
+    let addr_manager = mock_app
+        .instantiate_contract(
+            manager_old_code_id,
+            Addr::unchecked("deployer-manager"),
+            &OldInstantiateMsg {},
+            &[],
+            "my-collection-manager",
+            Some(admin_addr.to_string()),
+        )
+        .expect("Failed to instantiate old collection manager");
+    // Arrange migration
+    let new_code = Box::new(
+        ContractWrapper::new(execute, instantiate, query)
+            .with_reply(reply)
+            .with_sudo(sudo)
+            .with_migrate(migrate),
+    );
+    let manager_new_code_id = mock_app.store_code(new_code);
+    let beneficiary_addr = Addr::unchecked("beneficiary");
+    let new_payment_params = PaymentParams::new(
+        beneficiary_addr.to_owned(),
+        Some(Coin {
+            denom: "silver".to_owned(),
+            amount: Uint128::from(23u16),
+        }),
+    );
+    let migrate_msg = MigrateMsg {
+        payment_params: new_payment_params.to_owned(),
+        owner: Some(admin_addr.to_string()),
+        collections: vec![],
+    };
+
+    // Act
+    let result = mock_app.migrate_contract(
+        admin_addr.to_owned(),
+        addr_manager.to_owned(),
+        &migrate_msg,
+        manager_new_code_id,
+    );
+
+    // Assert
+    assert!(result.is_ok(), "Failed to migrate the contract");
+    let result = result.unwrap();
+    let expected_migrate_event = Event::new("migrate")
         .add_attribute("_contract_address", addr_manager.to_owned())
         .add_attribute("code_id", "2".to_owned());
     result.assert_event(&expected_migrate_event);
     let expected_migrate_event2 = Event::new("wasm-my-collection-manager")
         .add_attribute("_contract_address", addr_manager.to_owned())
-        .add_attribute("update-contract-version", "0.1.0")
+        .add_attribute("update-contract-version", "0.2.0")
+        .add_attribute("update-owner", admin_addr.to_owned())
         .add_attribute("update-payment-params-beneficiary", beneficiary_addr)
         .add_attribute("update-payment-params-mint-price-denom", "silver")
         .add_attribute("update-payment-params-mint-price-amount", "23");
@@ -487,3 +1158,396 @@ fn test_migrate_payment_params() {
         }
     );
 }
+
+#[test]
+fn test_length_priced_mint() {
+    // Arrange
+    let sender_addr = Addr::unchecked("sender");
+    let silver = |amount: u16| Coin {
+        amount: Uint128::from(amount),
+        denom: "silver".to_owned(),
+    };
+    let mut mock_app = AppBuilder::default().build(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender_addr, vec![silver(600)])
+            .expect("Failed to init bank balances");
+    });
+    let beneficiary = Addr::unchecked("beneficiary");
+    let length_prices = vec![
+        LengthPrice {
+            min_length: 3,
+            price: silver(500),
+        },
+        LengthPrice {
+            min_length: 4,
+            price: silver(100),
+        },
+        LengthPrice {
+            min_length: 5,
+            price: silver(10),
+        },
+    ];
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams {
+            length_prices: length_prices.to_owned(),
+            ..PaymentParams::new(beneficiary.to_owned(), None)
+        },
+    );
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+    let register_msg = |name: &str| ExecuteMsg::PassThrough {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: name.to_owned(),
+            owner: "owner".to_owned(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
+    };
+
+    // Act
+    let too_short_result = mock_app.execute_contract(
+        sender_addr.to_owned(),
+        addr_manager.to_owned(),
+        &register_msg("ab"),
+        &[],
+    );
+    let short_result = mock_app.execute_contract(
+        sender_addr.to_owned(),
+        addr_manager.to_owned(),
+        &register_msg("abc"),
+        &[silver(500)],
+    );
+    let long_result = mock_app.execute_contract(
+        sender_addr.to_owned(),
+        addr_manager.to_owned(),
+        &register_msg("alice"),
+        &[silver(100)],
+    );
+
+    // Assert
+    assert!(
+        too_short_result.is_err(),
+        "Expected a name below the length prices to be rejected"
+    );
+    assert!(short_result.is_ok(), "Failed to register a short name");
+    assert!(long_result.is_ok(), "Failed to register a long name");
+    assert_eq!(
+        mock_app
+            .wrap()
+            .query_all_balances(beneficiary)
+            .expect("Failed to get beneficiary balances"),
+        vec![silver(510)]
+    );
+    assert_eq!(
+        mock_app
+            .wrap()
+            .query_all_balances(sender_addr)
+            .expect("Failed to get sender balances"),
+        vec![silver(90)]
+    );
+    let payment_params = mock_app
+        .wrap()
+        .query_wasm_smart::<GetPaymentParamsResponse>(&addr_manager, &QueryMsg::GetPaymentParams)
+        .expect("Failed to query payment params")
+        .payment_params;
+    assert_eq!(payment_params.length_prices, length_prices);
+}
+
+#[test]
+fn test_english_auction() {
+    // Arrange
+    let alice_addr = Addr::unchecked("alice");
+    let bob_addr = Addr::unchecked("bob");
+    let silver = |amount: u16| Coin {
+        amount: Uint128::from(amount),
+        denom: "silver".to_owned(),
+    };
+    let mut mock_app = AppBuilder::default().build(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice_addr, vec![silver(200)])
+            .expect("Failed to init bank balances");
+        router
+            .bank
+            .init_balance(storage, &bob_addr, vec![silver(200)])
+            .expect("Failed to init bank balances");
+    });
+    let beneficiary = Addr::unchecked("beneficiary");
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams::new(beneficiary.to_owned(), None),
+    );
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+    let end = mock_app.block_info().time.plus_seconds(100);
+    mock_app
+        .execute_contract(
+            Addr::unchecked("admin"),
+            addr_manager.to_owned(),
+            &ExecuteMsg::OpenAuction {
+                collection: addr_collection.to_string(),
+                token_id: "gold".to_owned(),
+                reserve_price: silver(100),
+                end,
+                min_increment: Uint128::from(10u16),
+            },
+            &[],
+        )
+        .expect("Failed to open auction");
+    let bid_msg = ExecuteMsg::Bid { auction_id: 1 };
+    mock_app
+        .execute_contract(
+            alice_addr.to_owned(),
+            addr_manager.to_owned(),
+            &bid_msg,
+            &[silver(100)],
+        )
+        .expect("Failed to bid as alice");
+
+    // Act
+    let low_bid_result = mock_app.execute_contract(
+        bob_addr.to_owned(),
+        addr_manager.to_owned(),
+        &bid_msg,
+        &[silver(105)],
+    );
+    let outbid_result = mock_app.execute_contract(
+        bob_addr.to_owned(),
+        addr_manager.to_owned(),
+        &bid_msg,
+        &[silver(150)],
+    );
+    let direct_mint_result = mock_app.execute_contract(
+        alice_addr.to_owned(),
+        addr_manager.to_owned(),
+        &ExecuteMsg::PassThrough {
+            collection: addr_collection.to_string(),
+            message: CollectionExecuteMsg::Mint {
+                token_id: "gold".to_owned(),
+                owner: alice_addr.to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            mint_options: None,
+        },
+        &[],
+    );
+    let early_settle_result = mock_app.execute_contract(
+        alice_addr.to_owned(),
+        addr_manager.to_owned(),
+        &ExecuteMsg::SettleAuction { auction_id: 1 },
+        &[],
+    );
+    mock_app.update_block(|block| block.time = end);
+    let settle_result = mock_app.execute_contract(
+        alice_addr.to_owned(),
+        addr_manager.to_owned(),
+        &ExecuteMsg::SettleAuction { auction_id: 1 },
+        &[],
+    );
+
+    // Assert
+    assert!(
+        low_bid_result.is_err(),
+        "Accepted a bid below the increment"
+    );
+    assert!(outbid_result.is_ok(), "Failed to outbid alice");
+    assert!(direct_mint_result.is_err(), "Minted an auctioned token id");
+    assert!(early_settle_result.is_err(), "Settled a running auction");
+    assert!(settle_result.is_ok(), "Failed to settle auction");
+    assert_eq!(
+        mock_app
+            .wrap()
+            .query_wasm_smart::<OwnerOfResponse>(
+                &addr_collection,
+                &CollectionQueryMsg::OwnerOf {
+                    token_id: "gold".to_owned(),
+                    include_expired: None,
+                },
+            )
+            .expect("Failed to query gold name")
+            .owner,
+        bob_addr.to_string()
+    );
+    for (addr, balance) in [(&alice_addr, 200), (&bob_addr, 50), (&beneficiary, 150)] {
+        assert_eq!(
+            mock_app
+                .wrap()
+                .query_all_balances(addr)
+                .expect("Failed to get balances"),
+            vec![silver(balance)]
+        );
+    }
+    let bid_history = mock_app
+        .wrap()
+        .query_wasm_smart::<BidHistoryResponse>(
+            &addr_manager,
+            &QueryMsg::BidHistory {
+                auction_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .expect("Failed to query bid history");
+    assert_eq!(
+        bid_history
+            .bids
+            .into_iter()
+            .map(|Bid { bidder, amount, .. }| (bidder, amount))
+            .collect::<Vec<_>>(),
+        vec![
+            (alice_addr, Uint128::from(100u16)),
+            (bob_addr, Uint128::from(150u16)),
+        ]
+    );
+    let active_auctions = mock_app
+        .wrap()
+        .query_wasm_smart::<ActiveAuctionsResponse>(
+            &addr_manager,
+            &QueryMsg::ActiveAuctions {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .expect("Failed to query active auctions");
+    assert_eq!(active_auctions.auctions, vec![]);
+}
+
+#[test]
+fn test_reclaim_expired_name() {
+    // Arrange
+    let alice_addr = Addr::unchecked("alice");
+    let bob_addr = Addr::unchecked("bob");
+    let carol_addr = Addr::unchecked("carol");
+    let silver = |amount: u16| Coin {
+        amount: Uint128::from(amount),
+        denom: "silver".to_owned(),
+    };
+    let mut mock_app = AppBuilder::default().build(|router, _api, storage| {
+        for addr in [&alice_addr, &bob_addr] {
+            router
+                .bank
+                .init_balance(storage, addr, vec![silver(100)])
+                .expect("Failed to init bank balances");
+        }
+    });
+    let (_, addr_manager) = instantiate_collection_manager(
+        &mut mock_app,
+        PaymentParams {
+            yearly_price: Some(silver(100)),
+            ..PaymentParams::new(Addr::unchecked("beneficiary"), None)
+        },
+    );
+    let (_, addr_collection) = instantiate_nameservice(&mut mock_app, addr_manager.to_string());
+    add_collection(&mut mock_app, &addr_manager, &addr_collection);
+    let approve_manager = |mock_app: &mut App, owner: &Addr| {
+        mock_app
+            .execute_contract(
+                owner.to_owned(),
+                addr_collection.to_owned(),
+                &CollectionExecuteMsg::ApproveAll {
+                    operator: addr_manager.to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .expect("Failed to approve the manager");
+    };
+    let register_msg = |name: &str, owner: &Addr| ExecuteMsg::PassThrough {
+        collection: addr_collection.to_string(),
+        message: CollectionExecuteMsg::Mint {
+            token_id: name.to_owned(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        },
+        mint_options: None,
+    };
+    let reclaim_msg = |name: &str| ExecuteMsg::Reclaim {
+        collection: addr_collection.to_string(),
+        token_id: name.to_owned(),
+        reassign: Some(Reassignment {
+            owner: carol_addr.to_string(),
+            years: 1,
+        }),
+    };
+    approve_manager(&mut mock_app, &alice_addr);
+
+    // Act
+    let register_result = mock_app.execute_contract(
+        alice_addr.to_owned(),
+        addr_manager.to_owned(),
+        &register_msg("gold", &alice_addr),
+        &[silver(100)],
+    );
+    let unapproved_result = mock_app.execute_contract(
+        bob_addr.to_owned(),
+        addr_manager.to_owned(),
+        &register_msg("bob", &bob_addr),
+        &[silver(100)],
+    );
+    mock_app.update_block(|block| block.time = block.time.plus_seconds(2 * SECONDS_PER_YEAR));
+    let reclaim_result = mock_app.execute_contract(
+        Addr::unchecked("admin"),
+        addr_manager.to_owned(),
+        &reclaim_msg("gold"),
+        &[],
+    );
+    let unapproved_reclaim_result = mock_app.execute_contract(
+        Addr::unchecked("admin"),
+        addr_manager.to_owned(),
+        &reclaim_msg("bob"),
+        &[],
+    );
+
+    // Assert
+    assert!(register_result.is_ok(), "Failed to register gold name");
+    assert!(
+        unapproved_result.is_ok(),
+        "Failed to register a name to an owner that did not approve the manager"
+    );
+    assert!(
+        reclaim_result.is_ok(),
+        "Failed to reclaim expired gold name"
+    );
+    let owner_of = |mock_app: &App, name: &str| {
+        mock_app
+            .wrap()
+            .query_wasm_smart::<OwnerOfResponse>(
+                &addr_collection,
+                &CollectionQueryMsg::OwnerOf {
+                    token_id: name.to_owned(),
+                    include_expired: None,
+                },
+            )
+            .expect("Failed to query name")
+            .owner
+    };
+    assert_eq!(owner_of(&mock_app, "gold"), carol_addr.to_string());
+    let unapproved_reclaim_result =
+        unapproved_reclaim_result.expect("Failed to drop the expired bob registration");
+    assert!(unapproved_reclaim_result.has_event(
+        &Event::new("wasm-my-collection-manager").add_attribute("reclaim-burn-failed", "bob")
+    ));
+    assert_eq!(owner_of(&mock_app, "bob"), bob_addr.to_string());
+    assert_eq!(
+        mock_app
+            .wrap()
+            .query_wasm_smart::<ExpiryResponse>(
+                &addr_manager,
+                &QueryMsg::Expiry {
+                    collection: addr_collection.to_string(),
+                    token_id: "bob".to_owned(),
+                },
+            )
+            .expect("Failed to query bob expiry"),
+        ExpiryResponse {
+            expires_at: None,
+            expired: false,
+        }
+    );
+}